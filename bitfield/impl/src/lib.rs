@@ -1,11 +1,23 @@
+mod specifier;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
-use syn::{parse_macro_input, Ident, ItemStruct};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, DeriveInput, Ident, ItemStruct};
+
+#[proc_macro_derive(BitfieldSpecifier)]
+pub fn derive_bitfield_specifier(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    specifier::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
 #[proc_macro_attribute]
 pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
-    let _ = args;
+    // `#[bitfield(const)]` makes the accessors const fns.
+    let constness = parse_macro_input!(args as Option<syn::Token![const]>);
     let input = parse_macro_input!(input as ItemStruct);
     let vis = &input.vis;
     let name = &input.ident;
@@ -15,6 +27,7 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut total_bits = quote! { #zero };
     let mut getters_setters = Vec::new();
     let mut bit_offset = quote! { #zero };
+    let mut bits_checks = Vec::new();
     if let syn::Fields::Named(fields) = &input.fields {
         for field in &fields.named {
             if let syn::Type::Path(tp) = &field.ty {
@@ -29,12 +42,41 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
                 let field_type = quote! {
                     <#ty_path as ::bitfield::Specifier>::Ty
                 };
+                // #[bits = N] documents the width of the field; check it
+                // against the type, reporting a mismatch at N.
+                match bits_attribute(field) {
+                    Ok(Some(lit)) => bits_checks.push(quote_spanned! {lit.span()=>
+                        const _: [(); #lit] = [(); #field_bits];
+                    }),
+                    Ok(None) => {}
+                    Err(err) => return err.to_compile_error().into(),
+                }
                 let ident = field.ident.as_ref().unwrap();
                 let getter = Ident::new(&format!("get_{}", ident), Span::call_site());
                 let setter = Ident::new(&format!("set_{}", ident), Span::call_site());
+                // Accessors are exactly as visible as the field, so a field
+                // without a visibility keeps them private to the module.
+                let accessor_vis = &field.vis;
+                // Trait methods cannot be called in a const fn, so const
+                // accessors go through the inherent const fns of the type the
+                // specifier names as its ConstSpecifier::Conv instead.
+                let (from_bits, into_bits) = if constness.is_some() {
+                    let conv = quote! {
+                        <<#ty_path as ::bitfield::__private::ConstSpecifier>::Conv>
+                    };
+                    (
+                        quote_spanned!(ty_path.span()=> #conv::__from_bits(value)),
+                        quote_spanned!(ty_path.span()=> #conv::__into_bits(value)),
+                    )
+                } else {
+                    (
+                        quote!(<#ty_path as ::bitfield::Specifier>::from_bits(value)),
+                        quote!(<#ty_path as ::bitfield::Specifier>::into_bits(value)),
+                    )
+                };
 
                 getters_setters.push(quote! {
-                    fn #getter(&self) -> #field_type {
+                    #accessor_vis #constness fn #getter(&self) -> #field_type {
                         let mut bits_placed: usize = #bit_offset as usize; // total bits placed so far
                         let mut remaining: u8 = #field_bits as u8;
                         let mut value: u64 = 0b0;
@@ -43,20 +85,20 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
                             let byte = bits_placed / 8;
                             let start = (bits_placed % 8) as u8;
                             let vacancy = 8 - start;
-                            let take = remaining.min(vacancy);
+                            let take = if remaining < vacancy { remaining } else { vacancy };
                             let end = start + take - 1;
 
-                            value |= ((((self.data[byte] & create_get_bit_mask(start, end)) as u64) >> start)<< previous_bits) as u64;
+                            value |= ((((self.data[byte] & ::bitfield::__private::create_get_bit_mask(start, end)) as u64) >> start)<< previous_bits) as u64;
                             previous_bits += (end - start + 1) as u64;
 
                             bits_placed += take as usize;
                             remaining -= take;
                         }
-                        value as #field_type
+                        #from_bits
                     }
 
-                     fn #setter(&mut self, value: #field_type) {
-                        let value = value as u64;
+                    #accessor_vis #constness fn #setter(&mut self, value: #field_type) {
+                        let value = #into_bits;
                         let mut bits_placed: usize = #bit_offset as usize; // total bits placed so far
                         let mut remaining: u8 = #field_bits as u8;
                         let mut num_bits_set: u64 = 0;
@@ -64,11 +106,11 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
                             let byte = bits_placed / 8;
                             let start = (bits_placed % 8) as u8;
                             let vacancy = 8 - start;
-                            let take = remaining.min(vacancy);
+                            let take = if remaining < vacancy { remaining } else { vacancy };
                             let end = start + take - 1;
 
-                            self.data[byte] &= !create_get_bit_mask(start, end);
-                            let slot_bits = ((value >> num_bits_set) & create_set_width_bit_mask(start, end)) << start;
+                            self.data[byte] &= !::bitfield::__private::create_get_bit_mask(start, end);
+                            let slot_bits = ((value >> num_bits_set) & ::bitfield::__private::create_set_width_bit_mask(start, end)) << start;
                             self.data[byte] |= slot_bits as u8;
                             num_bits_set += (end - start + 1) as u64;
 
@@ -96,9 +138,10 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
             data: [u8; #num_bytes]
         }
         const _: ::bitfield::checks::MultipleOfEight<[(); #rem_mod8]> = ();
+        #(#bits_checks)*
 
         impl #name {
-            fn new() -> Self {
+            pub const fn new() -> Self {
                 Self {
                     data: [0u8; #num_bytes]
                 }
@@ -109,3 +152,25 @@ pub fn bitfield(args: TokenStream, input: TokenStream) -> TokenStream {
     };
     expanded.into()
 }
+
+// The N of a `#[bits = N]` attribute on the field, if it has one.
+fn bits_attribute(field: &syn::Field) -> syn::Result<Option<syn::LitInt>> {
+    let mut bits = None;
+    for attr in &field.attrs {
+        if !attr.path().is_ident("bits") {
+            continue;
+        }
+        match &attr.meta {
+            syn::Meta::NameValue(syn::MetaNameValue {
+                value:
+                    syn::Expr::Lit(syn::ExprLit {
+                        lit: syn::Lit::Int(lit),
+                        ..
+                    }),
+                ..
+            }) => bits = Some(lit.clone()),
+            _ => return Err(syn::Error::new_spanned(attr, "expected `#[bits = N]`")),
+        }
+    }
+    Ok(bits)
+}
@@ -0,0 +1,85 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::{Data, DeriveInput, Fields};
+
+// `#[derive(BitfieldSpecifier)]` on a fieldless enum with a power-of-two
+// number of variants: the enum takes log2(variants) bits, stored as its
+// discriminant.
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "BitfieldSpecifier can only be derived for enums",
+            ))
+        }
+    };
+    if let Some(v) = variants.iter().find(|v| !matches!(v.fields, Fields::Unit)) {
+        return Err(syn::Error::new_spanned(
+            &v.fields,
+            "BitfieldSpecifier requires variants without fields",
+        ));
+    }
+    if !variants.len().is_power_of_two() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "BitfieldSpecifier expected a number of variants which is a power of 2",
+        ));
+    }
+    let bits = variants.len().trailing_zeros() as usize;
+
+    // Discriminants are whatever the compiler assigned, so both the range
+    // check and the conversions go through `as` casts rather than literals.
+    let idents: Vec<_> = variants.iter().map(|v| &v.ident).collect();
+    let range_checks = idents.iter().map(|ident| {
+        quote_spanned! {ident.span()=>
+            const _: ::bitfield::checks::CheckDiscriminantInRange<
+                [(); ((#name::#ident as usize) < (1usize << #bits)) as usize]
+            > = ();
+        }
+    });
+
+    // The conversions are inherent const fns, which the accessors of
+    // #[bitfield(const)] reach through ConstSpecifier, with the trait methods
+    // delegating to them.
+    Ok(quote! {
+        impl #name {
+            #[doc(hidden)]
+            pub const fn __from_bits(bits: u64) -> #name {
+                match bits {
+                    #(bits if bits == #name::#idents as u64 => #name::#idents,)*
+                    _ => ::core::panic!(::core::concat!(
+                        "bits out of range for ",
+                        ::core::stringify!(#name),
+                    )),
+                }
+            }
+
+            #[doc(hidden)]
+            pub const fn __into_bits(value: #name) -> u64 {
+                value as u64
+            }
+        }
+
+        impl ::bitfield::Specifier for #name {
+            const BITS: usize = #bits;
+            type Ty = #name;
+
+            fn from_bits(bits: u64) -> #name {
+                #name::__from_bits(bits)
+            }
+
+            fn into_bits(value: #name) -> u64 {
+                #name::__into_bits(value)
+            }
+        }
+
+        impl ::bitfield::__private::ConstSpecifier for #name {
+            type Conv = #name;
+        }
+
+        #(#range_checks)*
+    })
+}
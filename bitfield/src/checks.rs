@@ -52,3 +52,28 @@ impl TotalSizeIsMultipleOfEightBits for ZeroMod8 {
 }
 
 pub type MultipleOfEight<T> = <<T as Markers>::Marker as TotalSizeIsMultipleOfEightBits>::Check;
+
+pub enum True {}
+pub enum False {}
+
+pub trait DiscriminantInRange {
+    type Check;
+}
+
+pub trait Condition {
+    type Outcome;
+}
+
+impl Condition for [(); 0] {
+    type Outcome = False;
+}
+
+impl Condition for [(); 1] {
+    type Outcome = True;
+}
+
+impl DiscriminantInRange for True {
+    type Check = ();
+}
+
+pub type CheckDiscriminantInRange<T> = <<T as Condition>::Outcome as DiscriminantInRange>::Check;
@@ -10,12 +10,57 @@
 //
 // From the perspective of a user of this crate, they get all the necessary APIs
 // (macro, trait, struct) through the one bitfield crate.
-pub use bitfield_impl::bitfield;
+pub use bitfield_impl::{bitfield, BitfieldSpecifier};
 pub mod checks;
 
+// Not public API. Used by the code #[bitfield] generates, which refers to
+// everything through absolute `::bitfield::` paths so that it does not depend
+// on what the user imported.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::{create_get_bit_mask, create_set_width_bit_mask};
+
+    use crate::Specifier;
+
+    // Specifiers whose conversions are also available as inherent const fns,
+    // `Conv::__from_bits` and `Conv::__into_bits`, which the accessors of
+    // #[bitfield(const)] call because a const fn cannot call trait methods.
+    // Implemented for the B types, bool and enums deriving BitfieldSpecifier.
+    #[diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be used in a #[bitfield(const)] struct",
+        label = "has no const conversions",
+        note = "use one of the B types, bool or an enum deriving BitfieldSpecifier"
+    )]
+    pub trait ConstSpecifier: Specifier {
+        type Conv;
+    }
+
+    // The const conversions of bool, which cannot have inherent fns itself.
+    pub struct BoolConv;
+
+    impl BoolConv {
+        pub const fn __from_bits(bits: u64) -> bool {
+            bits != 0
+        }
+
+        pub const fn __into_bits(value: bool) -> u64 {
+            value as u64
+        }
+    }
+
+    impl ConstSpecifier for bool {
+        type Conv = BoolConv;
+    }
+}
+
 pub trait Specifier {
     const BITS: usize;
     type Ty;
+
+    // Conversions between the accessor type and the field's bits, held in the
+    // low `BITS` bits of a u64.
+    fn from_bits(bits: u64) -> Self::Ty;
+    fn into_bits(value: Self::Ty) -> u64;
 }
 
 pub struct FixedBits<const N: usize>();
@@ -24,9 +69,34 @@ macro_rules! fixed_bytes_aliases {
     ($($name:ident<$N:literal, $ty:ty>),* $(,)?) => {
         $(
             pub type $name = FixedBits<$N>;
+
+            impl FixedBits<$N> {
+                #[doc(hidden)]
+                pub const fn __from_bits(bits: u64) -> $ty {
+                    bits as $ty
+                }
+
+                #[doc(hidden)]
+                pub const fn __into_bits(value: $ty) -> u64 {
+                    value as u64
+                }
+            }
+
             impl Specifier for FixedBits<$N> {
                 const BITS: usize = $N;
                 type Ty = $ty;
+
+                fn from_bits(bits: u64) -> $ty {
+                    Self::__from_bits(bits)
+                }
+
+                fn into_bits(value: $ty) -> u64 {
+                    Self::__into_bits(value)
+                }
+            }
+
+            impl __private::ConstSpecifier for FixedBits<$N> {
+                type Conv = Self;
             }
         )*
     };
@@ -57,21 +127,78 @@ fixed_bytes_aliases! {
     B22<22, u32>,
     B23<23, u32>,
     B24<24, u32>,
+    B25<25, u32>,
+    B26<26, u32>,
+    B27<27, u32>,
+    B28<28, u32>,
+    B29<29, u32>,
+    B30<30, u32>,
+    B31<31, u32>,
+    B32<32, u32>,
+    B33<33, u64>,
+    B34<34, u64>,
+    B35<35, u64>,
+    B36<36, u64>,
+    B37<37, u64>,
+    B38<38, u64>,
+    B39<39, u64>,
+    B40<40, u64>,
+    B41<41, u64>,
+    B42<42, u64>,
+    B43<43, u64>,
+    B44<44, u64>,
+    B45<45, u64>,
+    B46<46, u64>,
+    B47<47, u64>,
+    B48<48, u64>,
+    B49<49, u64>,
+    B50<50, u64>,
+    B51<51, u64>,
+    B52<52, u64>,
+    B53<53, u64>,
+    B54<54, u64>,
+    B55<55, u64>,
+    B56<56, u64>,
+    B57<57, u64>,
+    B58<58, u64>,
+    B59<59, u64>,
+    B60<60, u64>,
+    B61<61, u64>,
+    B62<62, u64>,
+    B63<63, u64>,
+    B64<64, u64>,
+}
+
+impl Specifier for bool {
+    const BITS: usize = 1;
+    type Ty = bool;
+
+    fn from_bits(bits: u64) -> bool {
+        __private::BoolConv::__from_bits(bits)
+    }
+
+    fn into_bits(value: bool) -> u64 {
+        __private::BoolConv::__into_bits(value)
+    }
 }
 
-pub fn create_get_bit_mask(start: u8, end: u8) -> u8 {
+pub const fn create_get_bit_mask(start: u8, end: u8) -> u8 {
     let mut mask: u8 = 0b00000000;
-    for i in start..=end {
+    let mut i = start;
+    while i <= end {
         mask |= 1 << i;
+        i += 1;
     }
     mask
 }
 
-pub fn create_set_width_bit_mask(start: u8, end: u8) -> u64 {
+pub const fn create_set_width_bit_mask(start: u8, end: u8) -> u64 {
     debug_assert!(end >= start && (end - start) < 64);
     let mut mask: u64 = 0b0;
-    for i in 0..=end - start {
+    let mut i = 0;
+    while i <= end - start {
         mask |= 1 << i;
+        i += 1;
     }
     mask
 }
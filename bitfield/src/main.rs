@@ -71,8 +71,8 @@ impl<const M: usize, const N: usize> Bitfield<M, N> {
         let mut value: u64 = 0b0;
         let mut previous_bits: u64 = 0;
         for slot in &self.fields[field_index].slots {
-            value |= ((((self.data[slot.number] & slot.get_bit_mask) as u64) >> slot.start)
-                << previous_bits) as u64;
+            value |= (((self.data[slot.number] & slot.get_bit_mask) as u64) >> slot.start)
+                << previous_bits;
             previous_bits += (slot.end - slot.start + 1) as u64;
         }
         value
@@ -143,7 +143,7 @@ fn main() {
     const FIELD_SIZES: [u8; 4] = [9, 6, 13, 4];
     const DATA_SIZE: usize = calculate_data_size(&FIELD_SIZES);
     let mut bitfield: Bitfield<DATA_SIZE, { FIELD_SIZES.len() }> = Bitfield::new(FIELD_SIZES);
-    let a = 0b1100_0011_1;
+    let a = 0b1_1000_0111;
     let b = 0b101_010;
     let c = 0x1675;
     let d = 0b1110;
@@ -0,0 +1,51 @@
+// Fields wider than 24 bits use B25 through B64. Their accessors take and
+// return u32 up to B32 and u64 from B33 on. A bool field takes a single bit
+// and its accessors take and return bool.
+//
+//     ║ flag ║        a (B33)        ║        b (B64)        ║   c (B30)   ║
+//
+// Fields start at arbitrary bit offsets, so the wide ones span up to nine
+// bytes of storage.
+
+use bitfield::*;
+
+#[bitfield]
+pub struct Wide {
+    flag: bool,
+    a: B33,
+    b: B64,
+    c: B30,
+}
+
+fn main() {
+    assert_eq!(std::mem::size_of::<Wide>(), 16);
+    assert_eq!(<B25 as Specifier>::BITS, 25);
+    assert_eq!(<bool as Specifier>::BITS, 1);
+
+    let mut wide = Wide::new();
+    assert!(!wide.get_flag());
+    assert_eq!(wide.get_a(), 0u64);
+    assert_eq!(wide.get_b(), 0u64);
+    assert_eq!(wide.get_c(), 0u32);
+
+    let a = 0x1_2345_6789;
+    let b = 0xfedc_ba98_7654_3210;
+    let c = 0x2bad_cafe;
+
+    wide.set_flag(true);
+    wide.set_a(a);
+    wide.set_b(b);
+    wide.set_c(c);
+
+    assert!(wide.get_flag());
+    assert_eq!(wide.get_a(), a);
+    assert_eq!(wide.get_b(), b);
+    assert_eq!(wide.get_c(), c);
+
+    wide.set_flag(false);
+    wide.set_b(u64::MAX);
+    assert!(!wide.get_flag());
+    assert_eq!(wide.get_a(), a);
+    assert_eq!(wide.get_b(), u64::MAX);
+    assert_eq!(wide.get_c(), c);
+}
@@ -0,0 +1,88 @@
+// Accessors take the visibility of their field, so a `pub struct` bitfield
+// can be used from other modules through its `pub` fields while fields without
+// a visibility stay private to the defining module, like ordinary fields.
+//
+// `new()` is a const fn, and `#[bitfield(const)]` makes the accessors const
+// fns as well, which lets bitfields be built in `const` and `static`
+// initializers. That works for fields of the B types, bool and any enum
+// deriving BitfieldSpecifier.
+
+mod registers {
+    use bitfield::*;
+
+    #[bitfield(const)]
+    pub struct Control {
+        pub enable: bool,
+        pub(crate) mode: B3,
+        reserved: B4,
+        pub divider: B24,
+    }
+
+    // The accessors of `reserved` are private; code in this module can still
+    // use them, including from const fns.
+    impl Control {
+        pub const fn with_reserved(mut self, reserved: u8) -> Self {
+            self.set_reserved(reserved);
+            self
+        }
+
+        pub const fn reserved(&self) -> u8 {
+            self.get_reserved()
+        }
+    }
+
+    #[bitfield]
+    pub struct Status {
+        pub ready: bool,
+        pub code: B7,
+    }
+
+    #[derive(BitfieldSpecifier, Debug, PartialEq)]
+    pub enum Speed {
+        Off,
+        Slow,
+        Fast,
+        Turbo,
+    }
+
+    #[bitfield(const)]
+    pub struct Fan {
+        pub speed: Speed,
+        pub duty: B6,
+    }
+}
+
+use registers::{Control, Fan, Status, Speed};
+
+const DEFAULT: Control = {
+    let mut control = Control::new();
+    control.set_enable(true);
+    control.set_mode(0b101);
+    control.set_divider(0x12_3456);
+    control.with_reserved(0b1001)
+};
+
+static DEFAULT_DIVIDER: u32 = DEFAULT.get_divider();
+
+static FAN: Fan = {
+    let mut fan = Fan::new();
+    fan.set_speed(Speed::Fast);
+    fan.set_duty(40);
+    fan
+};
+
+fn main() {
+    assert!(DEFAULT.get_enable());
+    assert_eq!(DEFAULT.get_mode(), 0b101);
+    assert_eq!(DEFAULT.reserved(), 0b1001);
+    assert_eq!(DEFAULT_DIVIDER, 0x12_3456);
+
+    let mut status = Status::new();
+    status.set_ready(true);
+    status.set_code(42);
+    assert!(status.get_ready());
+    assert_eq!(status.get_code(), 42);
+
+    assert_eq!(FAN.get_speed(), Speed::Fast);
+    assert_eq!(FAN.get_duty(), 40);
+}
@@ -0,0 +1,48 @@
+// The code generated by #[bitfield] refers to everything it needs through
+// absolute `::bitfield::` paths, so the macro works without a glob import of
+// the bitfield crate. Specifier types can be imported one by one, named by
+// their full path, derived locally or implemented by hand, whatever their name.
+
+use bitfield::{bitfield, BitfieldSpecifier, Specifier, B4};
+
+pub struct B3;
+
+impl Specifier for B3 {
+    const BITS: usize = 3;
+    type Ty = u8;
+
+    fn from_bits(bits: u64) -> u8 {
+        bits as u8
+    }
+
+    fn into_bits(value: u8) -> u64 {
+        value as u64
+    }
+}
+
+#[derive(BitfieldSpecifier, Debug, PartialEq)]
+pub enum Level {
+    Low,
+    High,
+}
+
+#[bitfield]
+pub struct Packet {
+    kind: B3,
+    level: Level,
+    length: B4,
+    payload: bitfield::B24,
+}
+
+fn main() {
+    let mut packet = Packet::new();
+    packet.set_kind(0b110);
+    packet.set_level(Level::High);
+    packet.set_length(9);
+    packet.set_payload(0xab_cdef);
+
+    assert_eq!(packet.get_kind(), 0b110);
+    assert_eq!(packet.get_level(), Level::High);
+    assert_eq!(packet.get_length(), 9);
+    assert_eq!(packet.get_payload(), 0xab_cdef);
+}
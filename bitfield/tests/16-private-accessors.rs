@@ -0,0 +1,21 @@
+// Fields without a visibility get private accessors, just like the fields of
+// an ordinary struct are private, so they cannot be called from outside the
+// module that defines the bitfield.
+
+mod registers {
+    use bitfield::*;
+
+    #[bitfield]
+    pub struct Control {
+        pub enable: bool,
+        reserved: B7,
+    }
+}
+
+use registers::Control;
+
+fn main() {
+    let mut control = Control::new();
+    control.set_enable(true);
+    control.set_reserved(0);
+}
@@ -0,0 +1,28 @@
+// A const fn cannot call trait methods, so the accessors of #[bitfield(const)]
+// need const conversions that a hand-written Specifier impl does not provide.
+// Using one is an error pointing at the field type.
+
+use bitfield::*;
+
+pub struct Nibble;
+
+impl Specifier for Nibble {
+    const BITS: usize = 4;
+    type Ty = u8;
+
+    fn from_bits(bits: u64) -> u8 {
+        bits as u8
+    }
+
+    fn into_bits(value: u8) -> u64 {
+        value as u64
+    }
+}
+
+#[bitfield(const)]
+pub struct Register {
+    low: Nibble,
+    high: B4,
+}
+
+fn main() {}
@@ -6,11 +6,16 @@ fn tests() {
     t.pass("tests/03-accessors.rs");
     t.compile_fail("tests/04-multiple-of-8bits.rs");
     t.pass("tests/05-accessor-signatures.rs");
-    //t.pass("tests/06-enums.rs");
-    //t.pass("tests/07-optional-discriminant.rs");
-    //t.compile_fail("tests/08-non-power-of-two.rs");
-    //t.compile_fail("tests/09-variant-out-of-range.rs");
-    //t.pass("tests/10-bits-attribute.rs");
-    //t.compile_fail("tests/11-bits-attribute-wrong.rs");
+    t.pass("tests/06-enums.rs");
+    t.pass("tests/07-optional-discriminant.rs");
+    t.compile_fail("tests/08-non-power-of-two.rs");
+    t.compile_fail("tests/09-variant-out-of-range.rs");
+    t.pass("tests/10-bits-attribute.rs");
+    t.compile_fail("tests/11-bits-attribute-wrong.rs");
     t.pass("tests/12-accessors-edge.rs");
+    t.pass("tests/13-wide-fields-and-bool.rs");
+    t.pass("tests/14-visibility-and-const.rs");
+    t.pass("tests/15-selective-imports.rs");
+    t.compile_fail("tests/16-private-accessors.rs");
+    t.compile_fail("tests/17-const-hand-written-specifier.rs");
 }
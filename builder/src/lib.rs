@@ -1,5 +1,5 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{parse_macro_input, DeriveInput};
 
 // Register the `builder` helper attribute so it is allowed on fields
@@ -7,6 +7,9 @@ use syn::{parse_macro_input, DeriveInput};
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let ident = input.ident;
+    // Named after the struct so that it cannot clash with a `FieldInfo` of
+    // the user's own.
+    let field_info = format_ident!("{}FieldInfo", ident);
 
     let data_struct = match &input.data {
         syn::Data::Struct(ds) => ds,
@@ -22,6 +25,9 @@ pub fn derive(input: TokenStream) -> TokenStream {
     let mut setters = Vec::new();
     let mut build_inits = Vec::new();
     let mut each_setter = Vec::new();
+    let mut field_infos = Vec::new();
+    let mut is_set_arms = Vec::new();
+    let mut missing_checks = Vec::new();
     match &data_struct.fields {
         syn::Fields::Named(fields) => {
             for f in &fields.named {
@@ -29,6 +35,9 @@ pub fn derive(input: TokenStream) -> TokenStream {
                 let field_type = &f.ty;
                 let mut same_name_as_each = false;
                 let mut has_each = false;
+                let mut validate_with: Option<syn::Path> = None;
+                let mut range: Option<syn::Expr> = None;
+                let mut error: Option<syn::Type> = None;
                 for attr in &f.attrs {
                     if attr.path().is_ident("builder") {
                         let parse_result = attr.parse_nested_meta(|meta| {
@@ -54,10 +63,21 @@ pub fn derive(input: TokenStream) -> TokenStream {
                                     }
                                 });
                                 ::core::result::Result::Ok(())
+                            } else if meta.path.is_ident("validate_with") {
+                                let lit: syn::LitStr = meta.value()?.parse()?;
+                                validate_with = Some(lit.parse()?);
+                                ::core::result::Result::Ok(())
+                            } else if meta.path.is_ident("range") {
+                                range = Some(meta.value()?.parse()?);
+                                ::core::result::Result::Ok(())
+                            } else if meta.path.is_ident("error") {
+                                let lit: syn::LitStr = meta.value()?.parse()?;
+                                error = Some(lit.parse()?);
+                                ::core::result::Result::Ok(())
                             } else {
                                 let msg = "expected `builder(each = \"...\")`";
                                 let error = syn::Error::new_spanned(&attr.meta, msg);
-                                return ::core::result::Result::Err(error);
+                                ::core::result::Result::Err(error)
                             }
                         });
                         if let ::core::result::Result::Err(e) = parse_result {
@@ -66,16 +86,73 @@ pub fn derive(input: TokenStream) -> TokenStream {
                     }
                 }
 
+                if has_each && (validate_with.is_some() || range.is_some()) {
+                    return syn::Error::new_spanned(
+                        f,
+                        "`validate_with` and `range` cannot be combined with `each`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+                if let (Some(error), None, None) = (&error, &validate_with, &range) {
+                    return syn::Error::new_spanned(
+                        error,
+                        "`error` requires `validate_with` or `range`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+
+                let name_str = name.as_ref().unwrap().to_string();
+                let type_name = type_name(field_type);
+                let required = !is_option(field_type) && !has_each;
+                field_infos.push(quote! {
+                    #field_info {
+                        name: #name_str,
+                        type_name: #type_name,
+                        required: #required,
+                        has_default: #has_each,
+                    }
+                });
+                is_set_arms.push(quote! {
+                    #name_str => self.#name.is_some()
+                });
+                if required {
+                    missing_checks.push(quote! {
+                        if self.#name.is_none() {
+                            missing.push(#name_str);
+                        }
+                    });
+                }
+
+                // Setter-time checks; a field with any of these gets a setter
+                // returning `Result<&mut Self, E>`. E is the type named by
+                // `error`, or `&'static str` like `build()` if there is none.
+                // Range errors are converted into E with `From<&'static str>`.
+                let error_ty = match &error {
+                    Some(ty) => quote!(#ty),
+                    None => quote!(&'static str),
+                };
+                let mut checks = Vec::new();
+                if let Some(range) = &range {
+                    let msg = format!("`{}` is out of range", name_str);
+                    checks.push(quote! {
+                        if !(#range).contains(&value) {
+                            return ::core::result::Result::Err(::core::convert::From::from(#msg));
+                        }
+                    });
+                }
+                if let Some(validate_with) = &validate_with {
+                    checks.push(quote! {
+                        #validate_with(&value)?;
+                    });
+                }
+
                 if is_option(field_type) {
                     builder_fields.push(quote! { #name: #field_type });
                     defaults.push(quote! { #name: None });
                     let inner_field_type = get_inner_type_from_option(field_type).unwrap();
-                    setters.push(quote! {
-                        pub fn #name(&mut self, value: #inner_field_type) -> &mut Self {
-                            self.#name = Some(value);
-                            self
-                        }
-                    });
+                    setters.push(setter(name, inner_field_type, &checks, &error_ty));
                     build_inits.push(quote! {
                         #name: if self.#name.is_some() {
                             self.#name.take()
@@ -91,12 +168,7 @@ pub fn derive(input: TokenStream) -> TokenStream {
                         defaults.push(quote! { #name: None });
                     }
                     if !same_name_as_each {
-                        setters.push(quote! {
-                            pub fn #name(&mut self, value: #field_type) -> &mut Self {
-                                self.#name = Some(value);
-                                self
-                            }
-                        });
+                        setters.push(setter(name, field_type, &checks, &error_ty));
                     }
                     let msg: &'static str = "missing field";
                     build_inits.push(quote! {
@@ -124,7 +196,32 @@ pub fn derive(input: TokenStream) -> TokenStream {
             #(#builder_fields,)*
         }
 
+        /// Static description of one field of the struct being built.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct #field_info {
+            pub name: &'static str,
+            pub type_name: &'static str,
+            /// `build()` fails unless this field has been set.
+            pub required: bool,
+            /// The builder starts out with a value for this field.
+            pub has_default: bool,
+        }
+
         impl Builder {
+            pub const FIELDS: &'static [#field_info] = &[#(#field_infos,)*];
+
+            pub fn missing_fields(&self) -> ::std::vec::Vec<&'static str> {
+                let mut missing = ::std::vec::Vec::new();
+                #(#missing_checks)*
+                missing
+            }
+
+            pub fn is_set(&self, field: &str) -> bool {
+                match field {
+                    #(#is_set_arms,)*
+                    _ => false,
+                }
+            }
 
             pub fn build(&mut self) -> ::core::result::Result<#ident, &'static str> {
                 ::core::result::Result::Ok( #ident {
@@ -149,6 +246,115 @@ pub fn derive(input: TokenStream) -> TokenStream {
     TokenStream::from(tokens)
 }
 
+fn setter(
+    name: &Option<syn::Ident>,
+    ty: &syn::Type,
+    checks: &[proc_macro2::TokenStream],
+    error_ty: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if checks.is_empty() {
+        quote! {
+            pub fn #name(&mut self, value: #ty) -> &mut Self {
+                self.#name = Some(value);
+                self
+            }
+        }
+    } else {
+        quote! {
+            pub fn #name(&mut self, value: #ty) -> ::core::result::Result<&mut Self, #error_ty> {
+                #(#checks)*
+                self.#name = Some(value);
+                ::core::result::Result::Ok(self)
+            }
+        }
+    }
+}
+
+// Render a type the way it is conventionally written, e.g. `Vec<String>`,
+// `[u8; 4]` or `dyn Fn(u8) -> u8`, rather than with the token spacing
+// `Vec < String >` that `to_string` on a token stream produces.
+fn type_name(ty: &syn::Type) -> String {
+    let mut pieces = Vec::new();
+    type_name_pieces(quote!(#ty), &mut pieces);
+    join_pieces(&pieces)
+}
+
+enum Piece {
+    Word(String),
+    Op(String),
+    Group(String),
+}
+
+fn type_name_pieces(tokens: proc_macro2::TokenStream, pieces: &mut Vec<Piece>) {
+    use proc_macro2::{Delimiter, Spacing, TokenTree};
+
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ident) => pieces.push(Piece::Word(ident.to_string())),
+            TokenTree::Literal(lit) => pieces.push(Piece::Word(lit.to_string())),
+            TokenTree::Punct(punct) => {
+                let mut op = punct.as_char().to_string();
+                // `::`, `->` and `=>` are the only multi-character operators
+                // that appear in types.
+                if punct.spacing() == Spacing::Joint {
+                    if let Some(TokenTree::Punct(next)) = tokens.peek() {
+                        if matches!((op.as_str(), next.as_char()), (":", ':') | ("-" | "=", '>')) {
+                            op.push(next.as_char());
+                            tokens.next();
+                        }
+                    }
+                }
+                pieces.push(Piece::Op(op));
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::None => {
+                        type_name_pieces(group.stream(), pieces);
+                        continue;
+                    }
+                };
+                let mut inner = Vec::new();
+                type_name_pieces(group.stream(), &mut inner);
+                pieces.push(Piece::Group(format!(
+                    "{}{}{}",
+                    open,
+                    join_pieces(&inner),
+                    close
+                )));
+            }
+        }
+    }
+}
+
+fn join_pieces(pieces: &[Piece]) -> String {
+    let mut out = String::new();
+    for (i, piece) in pieces.iter().enumerate() {
+        if i > 0 && space_between(&pieces[i - 1], piece) {
+            out.push(' ');
+        }
+        match piece {
+            Piece::Word(s) | Piece::Op(s) | Piece::Group(s) => out.push_str(s),
+        }
+    }
+    out
+}
+
+fn space_between(prev: &Piece, next: &Piece) -> bool {
+    let spaced = |op: &str| matches!(op, "->" | "=>" | "=" | "+");
+    match (prev, next) {
+        (Piece::Op(op), _) if op == "," || op == ";" || spaced(op) => true,
+        (_, Piece::Op(op)) => spaced(op),
+        // `for<'a> fn(&'a u8)` and `<T as Trait>::Assoc`.
+        (Piece::Op(op), _) => op == ">",
+        (Piece::Word(_), Piece::Group(_)) | (Piece::Group(_), Piece::Group(_)) => false,
+        _ => true,
+    }
+}
+
 fn is_option(ty: &syn::Type) -> bool {
     if let syn::Type::Path(tp) = ty {
         if let Some(seg) = tp.path.segments.last() {
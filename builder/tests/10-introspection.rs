@@ -0,0 +1,70 @@
+// Interactive tools built on top of a builder want to know which fields are
+// still outstanding before calling build(), and what fields exist at all.
+//
+// Generate a `missing_fields` method listing the required fields that have not
+// been set yet, an `is_set` method answering the same question for a single
+// field by name, and an associated `FIELDS` table describing every field of
+// the struct: its name, its type as written, whether build() requires it, and
+// whether the builder starts out with a value for it.
+//
+// The table's element type is named after the struct, `CommandFieldInfo`
+// here, so it does not clash with a `FieldInfo` type the user already has.
+// Type names are spaced the way rustfmt would write them.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Command {
+    executable: String,
+    #[builder(each = "arg")]
+    args: Vec<String>,
+    current_dir: Option<String>,
+    checksum: Option<[u8; 4]>,
+    filter: Option<Box<dyn Fn(&'static str) -> bool>>,
+}
+
+pub struct FieldInfo;
+
+fn main() {
+    let mut builder = Command::builder();
+    assert_eq!(builder.missing_fields(), vec!["executable"]);
+    assert!(!builder.is_set("executable"));
+    assert!(builder.is_set("args"));
+    assert!(!builder.is_set("current_dir"));
+    assert!(!builder.is_set("nonexistent"));
+
+    builder.executable("cargo".to_owned());
+    builder.current_dir("..".to_owned());
+    assert!(builder.missing_fields().is_empty());
+    assert!(builder.is_set("executable"));
+    assert!(builder.is_set("current_dir"));
+
+    let names: Vec<&str> = Builder::FIELDS.iter().map(|f| f.name).collect();
+    assert_eq!(
+        names,
+        ["executable", "args", "current_dir", "checksum", "filter"],
+    );
+
+    let executable = &Builder::FIELDS[0];
+    assert_eq!(executable.type_name, "String");
+    assert!(executable.required);
+    assert!(!executable.has_default);
+
+    let args = &Builder::FIELDS[1];
+    assert_eq!(args.type_name, "Vec<String>");
+    assert!(!args.required);
+    assert!(args.has_default);
+
+    let current_dir = &Builder::FIELDS[2];
+    assert_eq!(current_dir.type_name, "Option<String>");
+    assert!(!current_dir.required);
+    assert!(!current_dir.has_default);
+
+    let checksum: &CommandFieldInfo = &Builder::FIELDS[3];
+    assert_eq!(checksum.type_name, "Option<[u8; 4]>");
+
+    let filter = &Builder::FIELDS[4];
+    assert_eq!(filter.type_name, "Option<Box<dyn Fn(&'static str) -> bool>>");
+
+    let _ = FieldInfo;
+}
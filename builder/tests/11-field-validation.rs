@@ -0,0 +1,126 @@
+// Some inputs can be rejected as soon as they are handed to the builder rather
+// than when build() is eventually called.
+//
+// A field attribute #[builder(validate_with = "path::to::fn")] names a function
+// taking the field value by reference and returning Result<(), &'static str>.
+// The setter for that field runs the function and returns
+// Result<&mut Self, &'static str> instead of &mut Self, so a failed check is
+// reported at the call site and the field is left untouched.
+//
+// As a shorthand for the common numeric case, #[builder(range = 1..=65535)]
+// checks that the value lies within the given range. Both checks may appear on
+// the same field, and both work on Option fields by checking the inner value.
+//
+// A validator returning an error type other than &'static str is named along
+// with it as #[builder(validate_with = "...", error = "Type")], and the setter
+// then returns Result<&mut Self, Type>. Range errors on such a field are
+// converted into that type through its From<&'static str> impl.
+
+use derive_builder::Builder;
+
+#[derive(Builder)]
+pub struct Server {
+    #[builder(validate_with = "validate::non_empty")]
+    host: String,
+    #[builder(range = 1..=65535)]
+    port: u32,
+    #[builder(range = 1..10, validate_with = "validate::even")]
+    workers: Option<u8>,
+    #[builder(validate_with = "validate::known_user", error = "validate::UserError")]
+    user: String,
+    #[builder(
+        range = 0..=7,
+        validate_with = "validate::not_root_group",
+        error = "validate::UserError"
+    )]
+    group: u32,
+}
+
+mod validate {
+    pub fn non_empty(value: &String) -> Result<(), &'static str> {
+        if value.is_empty() {
+            Err("host must not be empty")
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn even(value: &u8) -> Result<(), &'static str> {
+        if value % 2 == 0 {
+            Ok(())
+        } else {
+            Err("workers must be even")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    pub enum UserError {
+        Unknown(String),
+        Invalid(&'static str),
+    }
+
+    impl From<&'static str> for UserError {
+        fn from(msg: &'static str) -> Self {
+            UserError::Invalid(msg)
+        }
+    }
+
+    pub fn known_user(value: &String) -> Result<(), UserError> {
+        match value.as_str() {
+            "root" | "www-data" => Ok(()),
+            _ => Err(UserError::Unknown(value.clone())),
+        }
+    }
+
+    pub fn not_root_group(value: &u32) -> Result<(), UserError> {
+        if *value == 0 {
+            Err(UserError::Invalid("group must not be root"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+use validate::UserError;
+
+fn main() {
+    let mut builder = Server::builder();
+    assert_eq!(builder.host(String::new()).err(), Some("host must not be empty"));
+    assert!(!builder.is_set("host"));
+    assert_eq!(builder.port(0).err(), Some("`port` is out of range"));
+    assert_eq!(builder.port(70000).err(), Some("`port` is out of range"));
+    assert_eq!(builder.workers(12).err(), Some("`workers` is out of range"));
+    assert_eq!(builder.workers(3).err(), Some("workers must be even"));
+    assert_eq!(
+        builder.user("nobody".to_owned()).err(),
+        Some(UserError::Unknown("nobody".to_owned())),
+    );
+    assert_eq!(
+        builder.group(8).err(),
+        Some(UserError::Invalid("`group` is out of range")),
+    );
+    assert_eq!(
+        builder.group(0).err(),
+        Some(UserError::Invalid("group must not be root")),
+    );
+
+    let server = builder
+        .host("localhost".to_owned())
+        .unwrap()
+        .port(8080)
+        .unwrap()
+        .workers(4)
+        .unwrap()
+        .user("www-data".to_owned())
+        .unwrap()
+        .group(3)
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(server.host, "localhost");
+    assert_eq!(server.port, 8080);
+    assert_eq!(server.workers, Some(4));
+    assert_eq!(server.user, "www-data");
+    assert_eq!(server.group, 3);
+}
@@ -10,4 +10,6 @@ fn tests() {
     t.pass("tests/07-repeated-field.rs");
     t.compile_fail("tests/08-unrecognized-attribute.rs");
     t.pass("tests/09-redefined-prelude-types.rs");
+    t.pass("tests/10-introspection.rs");
+    t.pass("tests/11-field-validation.rs");
 }
@@ -0,0 +1,258 @@
+use proc_macro2::{TokenStream as TokenStream2, TokenTree};
+use std::collections::HashSet;
+use syn::visit::Visit;
+use syn::{parse_quote, GenericArgument, Generics, PathArguments, Type, TypePath};
+
+// Type parameters, and associated type projections of them, that the fields
+// being formatted need a trait bound on.
+//
+// Inference follows the shape of the field type:
+//
+// - a type parameter `T` is bounded itself, and a projection such as
+//   `T::Value` or `<T as Trait>::Value` gets a where-clause bound;
+// - other paths, references, tuples, arrays and slices pass through to their
+//   type arguments and elements; lifetimes never need a bound;
+// - `PhantomData`, the marker types named by `#[debug(phantom = "...")]`, raw
+//   pointers and `fn` pointers are formatted whatever they are parameterized
+//   over, so they add nothing;
+// - the type being derived, by its plain name or as `Self`, adds nothing
+//   either: its impl is the one being generated, and holds wherever the
+//   other fields' bounds do. A same-named type from another module, such as
+//   `other::Name<T>`, is inferred like any other path;
+// - a trait object, `impl Trait` or macro type that mentions a type parameter
+//   says nothing about which of its parameters need the bound, so the whole
+//   field type is bounded instead, as in `Box<dyn Trait<T>>: Debug`.
+//
+// When the whole field type is bounded and it mentions the type being
+// derived, as in `link!(Chain<T>): Debug`, proving the impl would depend on
+// the impl itself, so the bound is left out. Projections such as
+// `<Name<T> as Trait>::Assoc` are bounded as usual: their `Debug` impl is that
+// of the associated type, not of the type being derived.
+#[derive(Default)]
+pub(crate) struct Constraints {
+    params: HashSet<syn::Ident>,
+    assoc: Vec<Type>,
+}
+
+// What inference needs to know about the type being derived.
+pub(crate) struct Scope<'a> {
+    name: &'a syn::Ident,
+    generics: HashSet<syn::Ident>,
+    phantoms: &'a [syn::Ident],
+    // The names the type being derived goes by inside its definition.
+    recursive: HashSet<syn::Ident>,
+}
+
+impl<'a> Scope<'a> {
+    pub(crate) fn new(
+        name: &'a syn::Ident,
+        generics: &Generics,
+        phantoms: &'a [syn::Ident],
+    ) -> Self {
+        Scope {
+            name,
+            generics: generics.type_params().map(|tp| tp.ident.clone()).collect(),
+            phantoms,
+            recursive: HashSet::from([name.clone(), syn::Ident::new("Self", name.span())]),
+        }
+    }
+}
+
+impl Constraints {
+    pub(crate) fn collect(&mut self, ty: &Type, scope: &Scope) {
+        let mut params = HashSet::new();
+        let mut assoc = Vec::new();
+        if collect_constraints_excluding_phantom(ty, scope, &mut params, &mut assoc) {
+            self.params.extend(params);
+            self.assoc.extend(assoc);
+        } else if !mentions(ty, &scope.recursive, true) {
+            self.assoc.push(ty.clone());
+        }
+    }
+
+    // Require `bound` of every collected type parameter and projection.
+    pub(crate) fn apply(&self, generics: &mut Generics, bound: &syn::Path) {
+        for type_param in generics.type_params_mut() {
+            if self.params.contains(&type_param.ident) {
+                type_param.bounds.push(parse_quote!(#bound));
+            }
+        }
+        let where_clause = generics.make_where_clause();
+        for ty in &self.assoc {
+            where_clause.predicates.push(parse_quote!(#ty: #bound));
+        }
+    }
+}
+
+// Returns false if `ty` contains a type whose requirements cannot be read off
+// its syntax, in which case the caller bounds `ty` as a whole.
+fn collect_constraints_excluding_phantom(
+    ty: &Type,
+    scope: &Scope,
+    used_params: &mut HashSet<syn::Ident>,
+    assoc_bounds: &mut Vec<Type>,
+) -> bool {
+    match ty {
+        Type::Path(tp) => handle_type_path(tp, scope, used_params, assoc_bounds),
+        Type::Reference(r) => {
+            collect_constraints_excluding_phantom(&r.elem, scope, used_params, assoc_bounds)
+        }
+        Type::Tuple(t) => t.elems.iter().all(|elem| {
+            collect_constraints_excluding_phantom(elem, scope, used_params, assoc_bounds)
+        }),
+        Type::Array(a) => {
+            collect_constraints_excluding_phantom(&a.elem, scope, used_params, assoc_bounds)
+        }
+        Type::Slice(s) => {
+            collect_constraints_excluding_phantom(&s.elem, scope, used_params, assoc_bounds)
+        }
+        Type::Group(g) => {
+            collect_constraints_excluding_phantom(&g.elem, scope, used_params, assoc_bounds)
+        }
+        Type::Paren(p) => {
+            collect_constraints_excluding_phantom(&p.elem, scope, used_params, assoc_bounds)
+        }
+        // Formatted as an address, or as the `!` that is never there.
+        Type::Ptr(_) | Type::BareFn(_) | Type::Never(_) => true,
+        // Trait objects, `impl Trait`, macros and anything unparsed.
+        _ => !mentions(ty, &scope.generics, false),
+    }
+}
+
+fn handle_type_path(
+    tp: &TypePath,
+    scope: &Scope,
+    used_params: &mut HashSet<syn::Ident>,
+    assoc_bounds: &mut Vec<Type>,
+) -> bool {
+    // Ignore PhantomData and the other markers completely
+    if let Some(seg) = tp.path.segments.last() {
+        if seg.ident == "PhantomData" || scope.phantoms.contains(&seg.ident) {
+            return true;
+        }
+    }
+
+    // Qualified path like <T as Trait>::Assoc
+    if let Some(q) = &tp.qself {
+        if mentions(&q.ty, &scope.generics, false) {
+            assoc_bounds.push(Type::Path(tp.clone()));
+        }
+        return true;
+    }
+
+    // The type being derived, which is Debug whenever this impl applies
+    if tp.path.is_ident("Self") {
+        return true;
+    }
+    // by its plain name; `other::Name<T>` is some other type
+    if tp.path.leading_colon.is_none() && tp.path.segments.len() == 1 {
+        let seg = &tp.path.segments[0];
+        if seg.ident == *scope.name && !scope.generics.contains(&seg.ident) {
+            return true;
+        }
+    }
+
+    // Bare type parameter like T
+    if tp.path.segments.len() == 1 {
+        let seg = tp.path.segments.last().unwrap();
+        if seg.arguments.is_empty() && scope.generics.contains(&seg.ident) {
+            used_params.insert(seg.ident.clone());
+            return true;
+        }
+    }
+
+    // Associated type projection like T::Assoc
+    if let Some(first) = tp.path.segments.first() {
+        if scope.generics.contains(&first.ident) && tp.path.segments.len() >= 2 {
+            assoc_bounds.push(Type::Path(tp.clone()));
+            return true;
+        }
+    }
+
+    // Recurse into generic arguments
+    let mut transparent = true;
+    for seg in &tp.path.segments {
+        match &seg.arguments {
+            PathArguments::AngleBracketed(ab) => {
+                for arg in &ab.args {
+                    if let GenericArgument::Type(t) = arg {
+                        transparent &= collect_constraints_excluding_phantom(
+                            t,
+                            scope,
+                            used_params,
+                            assoc_bounds,
+                        );
+                    }
+                }
+            }
+            // `Fn(T) -> U` sugar names a trait rather than a type.
+            PathArguments::Parenthesized(_) => {
+                transparent &= !mentions(&Type::Path(tp.clone()), &scope.generics, false);
+            }
+            PathArguments::None => {}
+        }
+    }
+    transparent
+}
+
+// Whether any of `names` is mentioned as a path segment anywhere in `ty`,
+// including in trait bounds, `fn` signatures and the tokens of a macro type.
+// With `leading`, a name only counts at the start of a path, as in `Name<T>`
+// or `Self::Value` but not `other::Name<T>`.
+fn mentions(ty: &Type, names: &HashSet<syn::Ident>, leading: bool) -> bool {
+    struct Finder<'a> {
+        names: &'a HashSet<syn::Ident>,
+        leading: bool,
+        found: bool,
+    }
+    impl<'a, 'ast> Visit<'ast> for Finder<'a> {
+        fn visit_type_path(&mut self, tp: &'ast TypePath) {
+            let mut segments = tp.path.segments.iter();
+            let found = if self.leading {
+                tp.path.leading_colon.is_none()
+                    && segments
+                        .next()
+                        .is_some_and(|seg| self.names.contains(&seg.ident))
+            } else {
+                segments.any(|seg| self.names.contains(&seg.ident))
+            };
+            if found {
+                self.found = true;
+                return;
+            }
+            syn::visit::visit_type_path(self, tp);
+        }
+        fn visit_macro(&mut self, mac: &'ast syn::Macro) {
+            self.found |= tokens_mention(mac.tokens.clone(), self.names, self.leading);
+        }
+        fn visit_type(&mut self, ty: &'ast Type) {
+            match ty {
+                Type::Verbatim(tokens) => {
+                    self.found |= tokens_mention(tokens.clone(), self.names, self.leading)
+                }
+                _ => syn::visit::visit_type(self, ty),
+            }
+        }
+    }
+    let mut f = Finder {
+        names,
+        leading,
+        found: false,
+    };
+    f.visit_type(ty);
+    f.found
+}
+
+fn tokens_mention(tokens: TokenStream2, names: &HashSet<syn::Ident>, leading: bool) -> bool {
+    // Whether the previous token was the `:` of a `::` path separator.
+    let mut after_colon = false;
+    tokens.into_iter().any(|tt| {
+        let found = match &tt {
+            TokenTree::Ident(ident) => names.contains(ident) && !(leading && after_colon),
+            TokenTree::Group(group) => tokens_mention(group.stream(), names, leading),
+            TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+        };
+        after_colon = matches!(&tt, TokenTree::Punct(p) if p.as_char() == ':');
+        found
+    })
+}
@@ -0,0 +1,83 @@
+use syn::LitStr;
+
+// The casing conventions accepted by `#[debug(rename_all = "...")]`, named
+// the way serde names them.
+#[derive(Clone, Copy)]
+pub(crate) enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    pub(crate) fn parse(lit: &LitStr) -> syn::Result<Self> {
+        Ok(match lit.value().as_str() {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "PascalCase" => RenameRule::Pascal,
+            "camelCase" => RenameRule::Camel,
+            "snake_case" => RenameRule::Snake,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "unknown rename rule; expected one of \"lowercase\", \"UPPERCASE\", \
+                     \"PascalCase\", \"camelCase\", \"snake_case\", \"SCREAMING_SNAKE_CASE\", \
+                     \"kebab-case\", \"SCREAMING-KEBAB-CASE\"",
+                ))
+            }
+        })
+    }
+
+    // Apply the rule to a snake_case field name or PascalCase variant name.
+    pub(crate) fn apply(self, name: &str) -> String {
+        let words = words(name);
+        let capitalize = |w: &String| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        };
+        match self {
+            RenameRule::Lower => words.concat(),
+            RenameRule::Upper => words.concat().to_uppercase(),
+            RenameRule::Pascal => words.iter().map(capitalize).collect(),
+            RenameRule::Camel => {
+                let mut out = words.first().cloned().unwrap_or_default();
+                out.extend(words.iter().skip(1).map(capitalize));
+                out
+            }
+            RenameRule::Snake => words.join("_"),
+            RenameRule::ScreamingSnake => words.join("_").to_uppercase(),
+            RenameRule::Kebab => words.join("-"),
+            RenameRule::ScreamingKebab => words.join("-").to_uppercase(),
+        }
+    }
+}
+
+// Lowercase words of an identifier, split at underscores and at the start of
+// each capitalized word.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for ch in name.chars() {
+        if (ch == '_' || ch.is_uppercase()) && !current.is_empty() {
+            words.push(std::mem::take(&mut current));
+        }
+        if ch != '_' {
+            current.extend(ch.to_lowercase());
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
@@ -0,0 +1,124 @@
+use crate::bound::{Constraints, Scope};
+use crate::format::FormatString;
+use crate::{struct_accesses, variant_pattern};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use std::collections::BTreeMap;
+use syn::{parse_quote, Attribute, DeriveInput, Fields, LitStr};
+
+pub(crate) fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let scope = Scope::new(name, &input.generics, &[]);
+    // Inferred bounds, grouped by the `core::fmt` trait they require.
+    let mut constraints: BTreeMap<&'static str, Constraints> = BTreeMap::new();
+    let mut infer = |fmt: &FormatString, fields: &Fields| {
+        for (index, fmt_trait) in fmt.field_traits() {
+            let field = fields.iter().nth(index).unwrap();
+            constraints
+                .entry(fmt_trait)
+                .or_default()
+                .collect(&field.ty, &scope);
+        }
+    };
+
+    let container_fmt = extract_display_attr(&input.attrs)?;
+    let body = match &input.data {
+        syn::Data::Struct(ds) => {
+            let fmt = container_fmt.ok_or_else(|| {
+                syn::Error::new_spanned(
+                    name,
+                    "#[derive(CustomDisplay)] requires a #[display(\"...\")] attribute",
+                )
+            })?;
+            let fmt = FormatString::parse(&fmt, &ds.fields, false)?;
+            infer(&fmt, &ds.fields);
+            let args = fmt.args(None, &struct_accesses(&ds.fields));
+            quote!(f.write_fmt(::core::format_args!(#args)))
+        }
+        syn::Data::Enum(de) => {
+            if let Some(fmt) = container_fmt {
+                return Err(syn::Error::new_spanned(
+                    fmt,
+                    "put #[display(\"...\")] on each variant rather than on the enum",
+                ));
+            }
+            let mut arms = Vec::new();
+            for v in &de.variants {
+                let fmt = extract_display_attr(&v.attrs)?.ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        &v.ident,
+                        "#[derive(CustomDisplay)] requires a #[display(\"...\")] attribute on every variant",
+                    )
+                })?;
+                let fmt = FormatString::parse(&fmt, &v.fields, false)?;
+                infer(&fmt, &v.fields);
+                let (pattern, accesses) = variant_pattern(v);
+                let args = fmt.args(None, &accesses);
+                arms.push(quote!(#pattern => f.write_fmt(::core::format_args!(#args)),));
+            }
+            if de.variants.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "#[derive(CustomDisplay)] only supports structs and enums",
+            ));
+        }
+    };
+
+    let mut generics = input.generics.clone();
+    for (fmt_trait, constraints) in &constraints {
+        let fmt_trait = format_ident!("{}", fmt_trait);
+        constraints.apply(&mut generics, &parse_quote!(::core::fmt::#fmt_trait));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+// #[display("...")] or #[display = "..."]
+fn extract_display_attr(attrs: &[Attribute]) -> syn::Result<Option<LitStr>> {
+    let mut fmt = None;
+    for attr in attrs {
+        if !attr.path().is_ident("display") {
+            continue;
+        }
+        let lit = match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(lit_str),
+                    ..
+                }) => lit_str.clone(),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected a format string: `#[display = \"...\"]`",
+                    ))
+                }
+            },
+            syn::Meta::List(list) => list.parse_args::<LitStr>()?,
+            syn::Meta::Path(path) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "expected `#[display(\"...\")]`",
+                ))
+            }
+        };
+        fmt = Some(lit);
+    }
+    Ok(fmt)
+}
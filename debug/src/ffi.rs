@@ -0,0 +1,196 @@
+use crate::helpers::Helpers;
+use crate::{extract_debug_attrs, is_integer, Position};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{Attribute, DataUnion, DeriveInput};
+
+// The parts of a `#[repr(...)]` attribute that decide how fields may be read.
+#[derive(Default)]
+pub(crate) struct Repr {
+    pub(crate) c: bool,
+    pub(crate) packed: bool,
+}
+
+impl Repr {
+    pub(crate) fn of(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut repr = Repr::default();
+        for attr in attrs {
+            if !attr.path().is_ident("repr") {
+                continue;
+            }
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("C") {
+                    repr.c = true;
+                } else if meta.path.is_ident("packed") {
+                    repr.packed = true;
+                }
+                // Skip the argument of `packed(N)` and `align(N)`.
+                if meta.input.peek(syn::token::Paren) {
+                    let _args;
+                    syn::parenthesized!(_args in meta.input);
+                }
+                Ok(())
+            })?;
+        }
+        Ok(repr)
+    }
+}
+
+// Primitive integers and floats, and arrays of them: types with no padding
+// and no invalid bit patterns. Returns the type as spelled by absolute path,
+// `::core::primitive::u32` for `u32` or `[::core::ffi::c_int; 4]` for
+// `[c_int; 4]`, since a name alone does not prove the type is the primitive.
+fn plain_type(ty: &syn::Type) -> Option<TokenStream2> {
+    const C_TYPES: &[&str] = &[
+        "c_char",
+        "c_schar",
+        "c_uchar",
+        "c_short",
+        "c_ushort",
+        "c_int",
+        "c_uint",
+        "c_long",
+        "c_ulong",
+        "c_longlong",
+        "c_ulonglong",
+        "c_float",
+        "c_double",
+    ];
+    match ty {
+        syn::Type::Array(a) => {
+            let elem = plain_type(&a.elem)?;
+            let len = &a.len;
+            Some(quote!([#elem; #len]))
+        }
+        syn::Type::Path(tp) if tp.qself.is_none() => {
+            let seg = tp.path.segments.last()?;
+            let ident = &seg.ident;
+            if is_integer(ty) || seg.arguments.is_empty() && (ident == "f32" || ident == "f64") {
+                Some(quote!(::core::primitive::#ident))
+            } else if seg.arguments.is_empty() && C_TYPES.iter().any(|c| ident == c) {
+                Some(quote!(::core::ffi::#ident))
+            } else {
+                None
+            }
+        }
+        syn::Type::Group(g) => plain_type(&g.elem),
+        syn::Type::Paren(p) => plain_type(&p.elem),
+        _ => None,
+    }
+}
+
+// A union has no record of which field was last written, so it is printed
+// without reading any field as such:
+//
+// - a `#[repr(C)]` union of integers, floats and arrays of them prints as
+//   `Name(0a 1b ...)`, the bytes that every field covers, which whichever
+//   field was written has initialized;
+// - `#[debug(union_tag = "field")]` on such a union prints `Name { field: .. }`,
+//   reading that field as its own type; it must be no larger than any other
+//   field, which is checked at compile time;
+// - any other union prints as `Name { .. }`.
+pub(crate) fn expand_union(input: &DeriveInput, data: &DataUnion) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let container_attrs = extract_debug_attrs(&input.attrs, Position::Union)?;
+    let display_name = match &container_attrs.rename {
+        Some(rename) => rename.value(),
+        None => name.unraw().to_string(),
+    };
+    for field in &data.fields.named {
+        if let Some(attr) = field.attrs.iter().find(|a| a.path().is_ident("debug")) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "#[debug] attributes on union fields are not supported",
+            ));
+        }
+    }
+
+    let tys: Vec<&syn::Type> = data.fields.named.iter().map(|f| &f.ty).collect();
+    let plain: Option<Vec<TokenStream2>> = tys.iter().map(|ty| plain_type(ty)).collect();
+    let readable = Repr::of(&input.attrs)?.c && plain.is_some();
+    // Field types are recognized by name. Fail to compile if a name is not the
+    // primitive it looks like, e.g. `type f32 = bool;` in the caller's scope.
+    let plain_checks = tys
+        .iter()
+        .zip(plain.unwrap_or_default())
+        .map(|(ty, plain)| quote_spanned!(ty.span()=> let _: fn(#ty) -> #plain = |x| x;));
+    let plain_checks = quote!(#(#plain_checks)*);
+    let body = match &container_attrs.union_tag {
+        Some(tag) => {
+            if !readable {
+                return Err(syn::Error::new_spanned(
+                    tag,
+                    "union_tag requires a #[repr(C)] union whose fields are all integers, floats or arrays of them",
+                ));
+            }
+            let field = data
+                .fields
+                .named
+                .iter()
+                .find(|f| f.ident.as_ref().unwrap().unraw() == tag.value())
+                .ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        tag,
+                        format_args!("no field `{}` in this union", tag.value()),
+                    )
+                })?;
+            let ident = &field.ident;
+            let ty = &field.ty;
+            let field_name = ident.as_ref().unwrap().unraw().to_string();
+            quote! {
+                #plain_checks
+                const {
+                    #(::core::assert!(
+                        ::core::mem::size_of::<#ty>() <= ::core::mem::size_of::<#tys>(),
+                        "union_tag field is larger than another field of the union",
+                    );)*
+                };
+                // SAFETY: the union is #[repr(C)], so every field starts at
+                // offset 0, and the tag is no larger than any field, so any
+                // write initialized all of its bytes. It is an integer, float
+                // or array of them, for which every bit pattern is valid.
+                let tag = unsafe { self.#ident };
+                f.debug_struct(#display_name)
+                    .field(#field_name, &tag)
+                    .finish_non_exhaustive()
+            }
+        }
+        None if readable => {
+            let bytes_adapter = Helpers {
+                bytes: true,
+                ..Helpers::default()
+            }
+            .definitions();
+            quote! {
+            #plain_checks
+            let mut len = ::core::mem::size_of::<Self>();
+            #(len = ::core::cmp::min(len, ::core::mem::size_of::<#tys>());)*
+            // SAFETY: the union is #[repr(C)], so every field starts at
+            // offset 0, and the fields are integers, floats or arrays of them
+            // with no padding, so whichever was written initialized at least
+            // the first `len` bytes.
+            let bytes = unsafe {
+                ::core::slice::from_raw_parts(self as *const Self as *const ::core::primitive::u8, len)
+            };
+            #bytes_adapter
+            f.debug_tuple(#display_name)
+                .field(&__DebugBytes(bytes))
+                .finish()
+            }
+        }
+        None => quote! {
+            f.debug_struct(#display_name).finish_non_exhaustive()
+        },
+    };
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
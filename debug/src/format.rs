@@ -0,0 +1,247 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Fields, LitStr};
+
+// A user format string rewritten into something `format_args!` accepts.
+//
+// Placeholders may refer to fields of the surrounding struct or variant as
+// `{self.host}`, `{self.0}`, or plain `{host}` and `{0}`. Each such reference
+// becomes a named argument `__self_host` bound to the field, since
+// `format_args!` itself has no notion of `self.field`. When the format belongs
+// to a single field, `{}` and `{0}` refer to that field instead.
+pub(crate) struct FormatString {
+    template: LitStr,
+    positional: bool,
+    named: Vec<(syn::Ident, usize)>,
+    // Every named reference with the formatting trait its spec selects.
+    uses: Vec<(usize, &'static str)>,
+    // The same for positional references to the field the format belongs to.
+    own_uses: Vec<&'static str>,
+    // The same format split into literal text and placeholders, for writing
+    // it out piece by piece.
+    pieces: Vec<Piece>,
+}
+
+enum Piece {
+    Text(String),
+    // A placeholder for the field at `index`, or for the field the format
+    // belongs to, with its `:spec` if any.
+    Arg { index: Option<usize>, spec: String },
+}
+
+impl FormatString {
+    // `own_field` is set for a field-level format and allows positional
+    // placeholders referring to that field.
+    pub(crate) fn parse(lit: &LitStr, fields: &Fields, own_field: bool) -> syn::Result<Self> {
+        let value = lit.value();
+        let mut template = String::with_capacity(value.len());
+        let mut positional = false;
+        let mut named: Vec<(syn::Ident, usize)> = Vec::new();
+        let mut uses = Vec::new();
+        let mut own_uses = Vec::new();
+        let mut implicit = 0;
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+
+        let mut rest = value.as_str();
+        while let Some(i) = rest.find(['{', '}']) {
+            template.push_str(&rest[..i]);
+            text.push_str(&rest[..i]);
+            rest = &rest[i..];
+            if rest.starts_with("{{") || rest.starts_with("}}") {
+                template.push_str(&rest[..2]);
+                text.push_str(&rest[..1]);
+                rest = &rest[2..];
+                continue;
+            }
+            if rest.starts_with('}') {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "invalid format string: unmatched `}` found; escape it as `}}`",
+                ));
+            }
+            let end = rest.find('}').ok_or_else(|| {
+                syn::Error::new(
+                    lit.span(),
+                    "invalid format string: expected `}` to close the placeholder",
+                )
+            })?;
+            if !text.is_empty() {
+                pieces.push(Piece::Text(std::mem::take(&mut text)));
+            }
+            let inner = &rest[1..end];
+            rest = &rest[end + 1..];
+            let (arg, spec) = match inner.find(':') {
+                Some(colon) => inner.split_at(colon),
+                None => (inner, ""),
+            };
+            let arg = arg.trim();
+
+            let numeric = arg.bytes().all(|b| b.is_ascii_digit());
+            if own_field && numeric {
+                let n = if arg.is_empty() {
+                    implicit += 1;
+                    implicit - 1
+                } else {
+                    arg.parse::<usize>().unwrap_or(usize::MAX)
+                };
+                if n != 0 {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "a field format can only refer to its own field positionally, as `{}` or `{0}`",
+                    ));
+                }
+                positional = true;
+                if !own_uses.contains(&fmt_trait(spec)) {
+                    own_uses.push(fmt_trait(spec));
+                }
+                pieces.push(Piece::Arg {
+                    index: None,
+                    spec: spec.to_owned(),
+                });
+                template.push_str("{0");
+                template.push_str(spec);
+                template.push('}');
+                continue;
+            }
+            if arg.is_empty() {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    "implicit positional arguments are not available here; refer to fields as `{self.field}`",
+                ));
+            }
+
+            let name = arg.strip_prefix("self.").unwrap_or(arg);
+            let index = field_index(fields, name).ok_or_else(|| {
+                syn::Error::new(
+                    lit.span(),
+                    format_args!("no field `{}` to refer to in this format string", name),
+                )
+            })?;
+            let ident = match &fields.iter().nth(index).unwrap().ident {
+                Some(ident) => format_ident!("__self_{}", ident.unraw()),
+                None => format_ident!("__self_{}", index),
+            };
+            template.push('{');
+            template.push_str(&ident.to_string());
+            template.push_str(spec);
+            template.push('}');
+            pieces.push(Piece::Arg {
+                index: Some(index),
+                spec: spec.to_owned(),
+            });
+            if !named.iter().any(|(_, i)| *i == index) {
+                named.push((ident, index));
+            }
+            let fmt_trait = fmt_trait(spec);
+            if !uses.contains(&(index, fmt_trait)) {
+                uses.push((index, fmt_trait));
+            }
+        }
+        template.push_str(rest);
+        text.push_str(rest);
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+
+        Ok(FormatString {
+            template: LitStr::new(&template, lit.span()),
+            positional,
+            named,
+            uses,
+            own_uses,
+            pieces,
+        })
+    }
+
+    pub(crate) fn literal(&self) -> &LitStr {
+        &self.template
+    }
+
+    // Indices of the fields referred to by name.
+    pub(crate) fn referenced_fields(&self) -> impl Iterator<Item = usize> + '_ {
+        self.named.iter().map(|(_, i)| *i)
+    }
+
+    // The fields referred to by name, each with the name of the `core::fmt`
+    // trait its placeholder formats it through.
+    pub(crate) fn field_traits(&self) -> impl Iterator<Item = (usize, &'static str)> + '_ {
+        self.uses.iter().copied()
+    }
+
+    // The traits the field a field-level format belongs to is formatted
+    // through by its positional placeholders.
+    pub(crate) fn own_traits(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.own_uses.iter().copied()
+    }
+
+    // The `format_args!` arguments: the template, the field itself when it is
+    // referred to positionally, then every referenced field by name.
+    // `accesses` holds one place expression per field, as in `fmt_fields`.
+    pub(crate) fn args(
+        &self,
+        own: Option<&TokenStream2>,
+        accesses: &[TokenStream2],
+    ) -> TokenStream2 {
+        let template = &self.template;
+        let own = own.filter(|_| self.positional).into_iter();
+        let names = self.named.iter().map(|(ident, _)| ident);
+        let values = self.named.iter().map(|(_, i)| &accesses[*i]);
+        quote!(#template #(, #own)* #(, #names = #values)*)
+    }
+
+    // Statements writing the format to the formatter `f` one piece at a time,
+    // so that placeholders without flags of their own, like `{}` or `{:?}`,
+    // are formatted with the flags `f` was given. Placeholders that set any
+    // flag keep exactly the ones they set. Takes the same arguments as `args`.
+    pub(crate) fn write(
+        &self,
+        own: Option<&TokenStream2>,
+        accesses: &[TokenStream2],
+    ) -> TokenStream2 {
+        let stmts = self.pieces.iter().map(|piece| match piece {
+            Piece::Text(text) => quote!(f.write_str(#text)?;),
+            Piece::Arg { index, spec } => {
+                let value = match index {
+                    Some(i) => &accesses[*i],
+                    None => own.expect("positional placeholder outside a field format"),
+                };
+                let fmt_trait = format_ident!("{}", fmt_trait(spec));
+                match spec.trim_start_matches(':') {
+                    "" | "?" | "x" | "X" | "o" | "b" | "e" | "E" | "p" => {
+                        quote!(::core::fmt::#fmt_trait::fmt(&#value, f)?;)
+                    }
+                    _ => {
+                        let template = LitStr::new(&format!("{{{}}}", spec), self.template.span());
+                        quote!(f.write_fmt(::core::format_args!(#template, #value))?;)
+                    }
+                }
+            }
+        });
+        quote!(#(#stmts)*)
+    }
+}
+
+// The `core::fmt` trait selected by a placeholder's `:spec`, going by its
+// trailing type character as in `{:08b}` or `{:#?}`.
+fn fmt_trait(spec: &str) -> &'static str {
+    match spec.chars().last() {
+        Some('?') => "Debug",
+        Some('x') => "LowerHex",
+        Some('X') => "UpperHex",
+        Some('o') => "Octal",
+        Some('b') => "Binary",
+        Some('e') => "LowerExp",
+        Some('E') => "UpperExp",
+        Some('p') => "Pointer",
+        _ => "Display",
+    }
+}
+
+fn field_index(fields: &Fields, name: &str) -> Option<usize> {
+    fields.iter().enumerate().position(|(i, f)| match &f.ident {
+        Some(ident) => ident.unraw() == name,
+        None => i.to_string() == name,
+    })
+}
@@ -0,0 +1,166 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+
+// Adapter types the generated `fmt` body needs to define. Each is emitted
+// only when some field is rendered through it.
+#[derive(Default)]
+pub(crate) struct Helpers {
+    pub(crate) with: bool,
+    pub(crate) bytes: bool,
+    pub(crate) fmt_fn: bool,
+    pub(crate) truncated_list: bool,
+    pub(crate) truncated_map: bool,
+    pub(crate) truncated_str: bool,
+}
+
+impl Helpers {
+    pub(crate) fn definitions(&self) -> TokenStream2 {
+        let mut defs = TokenStream2::new();
+        // Gives a `#[debug(with = "path")]` function a `Debug` impl.
+        if self.with {
+            defs.extend(quote! {
+                struct __DebugWith<'__a, __T: ?::core::marker::Sized>(
+                    &'__a __T,
+                    fn(&__T, &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+                );
+                impl<'__a, __T: ?::core::marker::Sized> ::core::fmt::Debug for __DebugWith<'__a, __T> {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        (self.1)(self.0, f)
+                    }
+                }
+            });
+        }
+        // Writes a field through a closure given the field's formatter, for
+        // `pretty` and `forward_flags` formats.
+        if self.fmt_fn {
+            defs.extend(quote! {
+                struct __DebugFn<__F>(__F)
+                where
+                    __F: ::core::ops::Fn(&mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result;
+                impl<__F> ::core::fmt::Debug for __DebugFn<__F>
+                where
+                    __F: ::core::ops::Fn(&mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result,
+                {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        (self.0)(f)
+                    }
+                }
+            });
+        }
+        // Renders a byte buffer as `0a 1b ...` for `#[debug(bytes)]`.
+        if self.bytes {
+            defs.extend(quote! {
+                struct __DebugBytes<'__a>(&'__a [::core::primitive::u8]);
+                impl<'__a> ::core::fmt::Debug for __DebugBytes<'__a> {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        for (i, byte) in self.0.iter().enumerate() {
+                            if i > 0 {
+                                f.write_str(" ")?;
+                            }
+                            ::core::write!(f, "{:02x}", byte)?;
+                        }
+                        ::core::result::Result::Ok(())
+                    }
+                }
+            });
+        }
+        // `#[debug(max_len = N)]` on a sequence or set: the first N items, then
+        // `... (M more)`, in brackets or braces.
+        if self.truncated_list {
+            defs.extend(quote! {
+                struct __DebugTruncated<'__a, __C: ?::core::marker::Sized> {
+                    items: &'__a __C,
+                    max_len: ::core::primitive::usize,
+                    set: ::core::primitive::bool,
+                }
+                impl<'__a, __C: ?::core::marker::Sized> ::core::fmt::Debug for __DebugTruncated<'__a, __C>
+                where
+                    &'__a __C: ::core::iter::IntoIterator,
+                    <&'__a __C as ::core::iter::IntoIterator>::Item: ::core::fmt::Debug,
+                {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        let mut iter = ::core::iter::IntoIterator::into_iter(self.items);
+                        let head = ::core::iter::Iterator::by_ref(&mut iter).take(self.max_len);
+                        if self.set {
+                            let mut out = f.debug_set();
+                            out.entries(head);
+                            let rest = ::core::iter::Iterator::count(iter);
+                            if rest > 0 {
+                                out.entry(&::core::format_args!("... ({} more)", rest));
+                            }
+                            out.finish()
+                        } else {
+                            let mut out = f.debug_list();
+                            out.entries(head);
+                            let rest = ::core::iter::Iterator::count(iter);
+                            if rest > 0 {
+                                out.entry(&::core::format_args!("... ({} more)", rest));
+                            }
+                            out.finish()
+                        }
+                    }
+                }
+            });
+        }
+        // `#[debug(max_len = N)]` on a map: the first N entries, then
+        // `... (M more)`, in braces.
+        if self.truncated_map {
+            defs.extend(quote! {
+                struct __DebugTruncatedMap<'__a, __C: ?::core::marker::Sized> {
+                    entries: &'__a __C,
+                    max_len: ::core::primitive::usize,
+                }
+                struct __DebugEntry<__E>(__E);
+                impl<__K: ::core::fmt::Debug, __V: ::core::fmt::Debug> ::core::fmt::Debug
+                    for __DebugEntry<(__K, __V)>
+                {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        ::core::fmt::Debug::fmt(&self.0 .0, f)?;
+                        f.write_str(": ")?;
+                        ::core::fmt::Debug::fmt(&self.0 .1, f)
+                    }
+                }
+                impl<'__a, __C: ?::core::marker::Sized> ::core::fmt::Debug for __DebugTruncatedMap<'__a, __C>
+                where
+                    &'__a __C: ::core::iter::IntoIterator,
+                    __DebugEntry<<&'__a __C as ::core::iter::IntoIterator>::Item>: ::core::fmt::Debug,
+                {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        let mut iter = ::core::iter::IntoIterator::into_iter(self.entries);
+                        let head = ::core::iter::Iterator::by_ref(&mut iter).take(self.max_len);
+                        let mut out = f.debug_set();
+                        out.entries(::core::iter::Iterator::map(head, __DebugEntry));
+                        let rest = ::core::iter::Iterator::count(iter);
+                        if rest > 0 {
+                            out.entry(&::core::format_args!("... ({} more)", rest));
+                        }
+                        out.finish()
+                    }
+                }
+            });
+        }
+        // `#[debug(max_len = N)]` on a string: the first N characters quoted,
+        // then `... (M more)`.
+        if self.truncated_str {
+            defs.extend(quote! {
+                struct __DebugTruncatedStr<'__a> {
+                    text: &'__a ::core::primitive::str,
+                    max_len: ::core::primitive::usize,
+                }
+                impl<'__a> ::core::fmt::Debug for __DebugTruncatedStr<'__a> {
+                    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        match self.text.char_indices().nth(self.max_len) {
+                            ::core::option::Option::Some((end, _)) => {
+                                ::core::fmt::Debug::fmt(&self.text[..end], f)?;
+                                let rest = self.text[end..].chars().count();
+                                ::core::write!(f, "... ({} more)", rest)
+                            }
+                            ::core::option::Option::None => ::core::fmt::Debug::fmt(self.text, f),
+                        }
+                    }
+                }
+            });
+        }
+        defs
+    }
+}
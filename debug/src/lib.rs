@@ -1,10 +1,22 @@
+mod bound;
+mod case;
+mod display;
+mod ffi;
+mod format;
+mod helpers;
+
+use crate::bound::{Constraints, Scope};
+use crate::case::RenameRule;
+use crate::ffi::Repr;
+use crate::format::FormatString;
+use crate::helpers::Helpers;
 use proc_macro::TokenStream;
-use quote::quote;
-use std::collections::HashSet;
-use syn::{
-    parse_macro_input, parse_quote, Attribute, DeriveInput, GenericArgument, PathArguments, Type,
-    TypePath, WherePredicate,
-};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote, ToTokens};
+use std::collections::BTreeMap;
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{parse_macro_input, parse_quote, Attribute, DeriveInput, Fields, Token, WherePredicate};
 
 // fn main() {
 //     let ty: syn::Type = syn::parse_str("Option<Vec<String>>").unwrap();
@@ -14,326 +26,960 @@ use syn::{
 #[proc_macro_derive(CustomDebug, attributes(debug))]
 pub fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(CustomDisplay, attributes(display))]
+pub fn derive_display(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    display::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
     let name = &input.ident;
 
-    let data_struct = match &input.data {
-        syn::Data::Struct(ds) => ds,
-        _ => {
-            return syn::Error::new_spanned(&input, "#[derive(CustomDebug)] only supports structs")
-                .to_compile_error()
-                .into();
-        }
+    if let syn::Data::Union(data) = &input.data {
+        return ffi::expand_union(input, data);
+    }
+    let position = match &input.data {
+        syn::Data::Enum(_) => Position::Enum,
+        _ => Position::Struct,
     };
+    let container_attrs = extract_debug_attrs(&input.attrs, position)?;
+    if let (syn::Data::Enum(_), Some(fmt)) = (&input.data, &container_attrs.fmt) {
+        return Err(syn::Error::new_spanned(
+            fmt,
+            "a format string on an enum is not supported; put it on each variant instead",
+        ));
+    }
 
     // Struct-level escape hatch: #[debug(bound = "...")]
-    let (override_bounds, _struct_fmt_unused) = extract_debug_attrs(&input.attrs);
+    let override_bounds = container_attrs.bounds.clone();
     let has_struct_level_override = !override_bounds.is_empty();
 
-    // Determine which generic type parameters are used in non-PhantomData fields
-    let mut used_type_params: HashSet<syn::Ident> = HashSet::new();
-    // Collect associated type projections like T::Assoc that require a where bound `T::Assoc: Debug`
-    let mut assoc_debug_bounds: Vec<Type> = Vec::new();
+    // Type parameters and projections used in non-PhantomData fields, grouped
+    // by the `core::fmt` trait the field is formatted through
+    let mut constraints: BTreeMap<&'static str, Constraints> = BTreeMap::new();
     // Collect field-level override bounds
     let mut field_level_overrides: Vec<WherePredicate> = Vec::new();
 
-    // Set of generic type parameter idents for quick lookup
-    let generic_idents: HashSet<syn::Ident> = input
-        .generics
-        .type_params()
-        .map(|tp| tp.ident.clone())
-        .collect();
-    for field in &data_struct.fields {
-        // Field-level escape hatch: #[debug(bound = "...")]
-        let (field_overrides, _fmt_ignored_here) = extract_debug_attrs(&field.attrs);
-        let has_field_override = !field_overrides.is_empty();
-        field_level_overrides.extend(field_overrides);
-
-        if has_struct_level_override {
-            // Skip all inference when struct-level override is present
-            continue;
+    let scope = Scope::new(name, &input.generics, &container_attrs.phantom);
+    let mut infer = Inference {
+        constraints: &mut constraints,
+        scope: &scope,
+    };
+    // Every field of the struct, or of all variants of the enum, takes part in
+    // bound inference, together with the struct or variant level formats.
+    let bodies = match &input.data {
+        syn::Data::Struct(ds) => vec![(
+            container_attrs.fmt.clone(),
+            container_attrs.pretty.clone(),
+            &ds.fields,
+        )],
+        syn::Data::Enum(de) => {
+            let mut bodies = Vec::new();
+            for v in &de.variants {
+                let variant_attrs = extract_debug_attrs(&v.attrs, Position::Variant)?;
+                bodies.push((variant_attrs.fmt, variant_attrs.pretty, &v.fields));
+            }
+            bodies
         }
-
-        if has_field_override {
-            // Skip inference for this field; we already added its manual bounds
-            continue;
+        syn::Data::Union(_) => unreachable!(),
+    };
+    for (fmt, pretty, fields) in bodies {
+        if !has_struct_level_override {
+            // A struct or variant format prints only the fields it refers to,
+            // each through the trait its placeholder selects.
+            for lit in fmt.iter().chain(&pretty) {
+                infer.format(lit, fields, None)?;
+            }
         }
 
-        collect_constraints_excluding_phantom(
-            &field.ty,
-            &generic_idents,
-            &mut used_type_params,
-            &mut assoc_debug_bounds,
-        );
+        for field in fields {
+            // Field-level escape hatch: #[debug(bound = "...")]
+            let field_attrs = extract_debug_attrs(&field.attrs, Position::Field)?;
+            let has_field_override = !field_attrs.bounds.is_empty();
+            field_level_overrides.extend(field_attrs.bounds.iter().cloned());
+
+            if has_struct_level_override {
+                // Skip all inference when struct-level override is present
+                continue;
+            }
+
+            if has_field_override {
+                // Skip inference for this field; we already added its manual bounds
+                continue;
+            }
+
+            if fmt.is_some() || field_attrs.skip {
+                // Not printed as a field of its own
+                continue;
+            }
+
+            if let Some(pretty) = field_attrs
+                .pretty
+                .as_ref()
+                .filter(|_| field_attrs.redact.is_none())
+            {
+                infer.format(pretty, fields, Some(&field.ty))?;
+            }
+
+            if field_attrs.bypasses_debug() {
+                // The field's own Debug impl is never called
+                continue;
+            }
+
+            match (field_attrs.shorthand, &field_attrs.fmt) {
+                (Some(Shorthand::Bytes), _) => {}
+                (Some(shorthand), _) => infer.field(&field.ty, shorthand.fmt_trait()),
+                (None, Some(fmt)) => infer.format(fmt, fields, Some(&field.ty))?,
+                (None, None) => infer.field(&field.ty, "Debug"),
+            }
+        }
     }
 
     // Build generics / where clause
     let mut generics = input.generics.clone();
-    if !has_struct_level_override {
-        // Add Debug bounds only for used type parameters
-        for type_param in generics.type_params_mut() {
-            if used_type_params.contains(&type_param.ident) {
-                type_param.bounds.push(parse_quote!(::core::fmt::Debug));
-            }
+    let where_clause = generics.make_where_clause();
+    if has_struct_level_override {
+        where_clause.predicates.extend(override_bounds);
+    } else {
+        where_clause.predicates.extend(field_level_overrides);
+        for (fmt_trait, constraints) in &constraints {
+            let fmt_trait = format_ident!("{}", fmt_trait);
+            constraints.apply(&mut generics, &parse_quote!(::core::fmt::#fmt_trait));
         }
     }
-    // Add where predicates for associated type projections and any overrides
-    {
-        let where_clause = generics.make_where_clause();
 
-        if has_struct_level_override {
-            for pred in override_bounds {
-                where_clause.predicates.push(pred);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut helpers = Helpers::default();
+    // The statements of `debug_fields`, reporting each field to `visitor`,
+    // when `#[debug(fields)]` asks for the method.
+    let visits;
+    let mut uses_visitor = false;
+    let body = match &input.data {
+        syn::Data::Struct(ds) => {
+            let mut accesses = struct_accesses(&ds.fields);
+            if Repr::of(&input.attrs)?.packed {
+                // Fields of a packed struct may be unaligned and cannot be
+                // borrowed, so format copies of them as `derive(Debug)` does.
+                for access in &mut accesses {
+                    *access = quote!({ #access });
+                }
             }
-        } else {
-            for ty in assoc_debug_bounds {
-                where_clause
-                    .predicates
-                    .push(parse_quote!(#ty: ::core::fmt::Debug));
+            let name = match &container_attrs.rename {
+                Some(rename) => rename.value(),
+                None => name.unraw().to_string(),
+            };
+            if container_attrs.fields {
+                let values = field_values(&container_attrs, &ds.fields, &accesses, &mut helpers)?;
+                visits = visit_fields(&values);
+                uses_visitor = !values.is_empty();
+            } else {
+                visits = TokenStream2::new();
+            }
+            fmt_fields(&name, &container_attrs, &ds.fields, &accesses, &mut helpers)?
+        }
+        syn::Data::Enum(de) => {
+            let mut arms = Vec::new();
+            let mut visit_arms = Vec::new();
+            for v in &de.variants {
+                let (pattern, accesses) = variant_pattern(v);
+                let mut variant_attrs = extract_debug_attrs(&v.attrs, Position::Variant)?;
+                variant_attrs.inherit(&container_attrs);
+                let variant = match (&variant_attrs.rename, container_attrs.rename_all) {
+                    (Some(rename), _) => rename.value(),
+                    (None, Some(rule)) => rule.apply(&v.ident.unraw().to_string()),
+                    (None, None) => v.ident.unraw().to_string(),
+                };
+                let fmt = fmt_fields(&variant, &variant_attrs, &v.fields, &accesses, &mut helpers)?;
+                arms.push(quote!(#pattern => #fmt,));
+                if container_attrs.fields {
+                    let values = field_values(&variant_attrs, &v.fields, &accesses, &mut helpers)?;
+                    let visit = visit_fields(&values);
+                    visit_arms.push(quote!(#pattern => { #visit }));
+                    uses_visitor |= !values.is_empty();
+                }
             }
-            for pred in field_level_overrides {
-                where_clause.predicates.push(pred);
+            visits = if !container_attrs.fields {
+                TokenStream2::new()
+            } else if de.variants.is_empty() {
+                quote!(match *self {})
+            } else if uses_visitor {
+                quote! {
+                    match self {
+                        #(#visit_arms)*
+                    }
+                }
+            } else {
+                TokenStream2::new()
+            };
+            if de.variants.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
             }
         }
-    }
-
-    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        syn::Data::Union(_) => unreachable!(),
+    };
 
-    let fields = data_struct.fields.iter().map(|f| {
-        let field_name = f.ident.as_ref().unwrap();
-        let field_name_str = field_name.to_string();
+    let helpers = helpers.definitions();
 
-        // parse #[debug = "format"] or #[debug("format")] via shared helper
-        let (_bounds_unused, fmt_pattern) = extract_debug_attrs(&f.attrs);
+    let visitor = if !uses_visitor {
+        format_ident!("_visitor")
+    } else {
+        format_ident!("visitor")
+    };
 
-        if let Some(fmt_str) = fmt_pattern {
-            quote! {
-                .field(#field_name_str, &format_args!(#fmt_str, self.#field_name))
-            }
-        } else {
-            quote! {
-                .field(#field_name_str, &self.#field_name)
+    // Only generated on request, since the method may clash with one of the
+    // type's own.
+    let debug_fields = container_attrs.fields.then(|| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Calls `visitor` with the name and `Debug` value of each field
+                /// the `Debug` output shows, as it shows them.
+                pub fn debug_fields(
+                    &self,
+                    #visitor: &mut impl ::core::ops::FnMut(
+                        &'static ::core::primitive::str,
+                        &dyn ::core::fmt::Debug,
+                    ),
+                ) {
+                    #helpers
+                    #visits
+                }
             }
         }
     });
 
-    let expanded = quote! {
+    Ok(quote! {
         impl #impl_generics ::core::fmt::Debug for #name #ty_generics #where_clause {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
-                f.debug_struct(stringify!(#name))
-                    #(#fields)*
-                    .finish()
+                #helpers
+                #body
             }
         }
+
+        #debug_fields
+    })
+}
+
+// `self.name` for named fields, `self.0` for tuple struct fields
+fn struct_accesses(fields: &Fields) -> Vec<TokenStream2> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| {
+            let member = match &f.ident {
+                Some(field_name) => syn::Member::Named(field_name.clone()),
+                None => syn::Member::Unnamed(syn::Index::from(i)),
+            };
+            quote!(self.#member)
+        })
+        .collect()
+}
+
+// A `match self` pattern for the variant together with one place expression
+// per field.
+fn variant_pattern(v: &syn::Variant) -> (TokenStream2, Vec<TokenStream2>) {
+    let variant = &v.ident;
+    // Bind fields positionally so user field names can never shadow the
+    // formatter `f`.
+    let bindings: Vec<syn::Ident> = (0..v.fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect();
+    let pattern = match &v.fields {
+        Fields::Named(named) => {
+            let idents = named.named.iter().map(|f| &f.ident);
+            quote!(Self::#variant { #(#idents: #bindings),* })
+        }
+        Fields::Unnamed(_) => quote!(Self::#variant(#(#bindings),*)),
+        Fields::Unit => quote!(Self::#variant),
     };
+    let accesses = bindings.iter().map(|b| quote!((*#b))).collect();
+    (pattern, accesses)
+}
+
+// Format strings must not leak the value of a skipped or redacted field.
+fn check_references(fmt: &FormatString, fields: &Fields) -> syn::Result<()> {
+    for index in fmt.referenced_fields() {
+        let field = fields.iter().nth(index).unwrap();
+        let attrs = extract_debug_attrs(&field.attrs, Position::Field)?;
+        if attrs.skip || attrs.redact.is_some() {
+            return Err(syn::Error::new_spanned(
+                fmt.literal(),
+                "format string refers to a skipped or redacted field",
+            ));
+        }
+    }
+    Ok(())
+}
 
-    TokenStream::from(expanded)
+// Bound inference for the fields of one struct or enum, collecting what each
+// field type needs by the `core::fmt` trait it is formatted through.
+struct Inference<'a, 'b> {
+    constraints: &'a mut BTreeMap<&'static str, Constraints>,
+    scope: &'a Scope<'b>,
 }
 
-fn collect_constraints_excluding_phantom(
-    ty: &Type,
-    generics: &HashSet<syn::Ident>,
-    used_params: &mut HashSet<syn::Ident>,
-    assoc_bounds: &mut Vec<Type>,
-) {
-    match ty {
-        Type::Path(tp) => handle_type_path(tp, generics, used_params, assoc_bounds),
-        Type::Reference(r) => {
-            collect_constraints_excluding_phantom(&r.elem, generics, used_params, assoc_bounds)
+impl Inference<'_, '_> {
+    fn field(&mut self, ty: &syn::Type, fmt_trait: &'static str) {
+        self.constraints
+            .entry(fmt_trait)
+            .or_default()
+            .collect(ty, self.scope);
+    }
+
+    // The fields `lit` refers to, and for a field-level format the field
+    // `own` it belongs to, by the traits of their placeholders. Fields with
+    // bounds of their own are left to those.
+    fn format(
+        &mut self,
+        lit: &syn::LitStr,
+        fields: &Fields,
+        own: Option<&syn::Type>,
+    ) -> syn::Result<()> {
+        let fmt = FormatString::parse(lit, fields, own.is_some())?;
+        for (index, fmt_trait) in fmt.field_traits() {
+            let field = fields.iter().nth(index).unwrap();
+            if extract_debug_attrs(&field.attrs, Position::Field)?
+                .bounds
+                .is_empty()
+            {
+                self.field(&field.ty, fmt_trait);
+            }
         }
-        Type::Tuple(t) => {
-            for elem in &t.elems {
-                collect_constraints_excluding_phantom(elem, generics, used_params, assoc_bounds);
+        if let Some(own) = own {
+            for fmt_trait in fmt.own_traits() {
+                self.field(own, fmt_trait);
             }
         }
-        Type::Array(a) => {
-            collect_constraints_excluding_phantom(&a.elem, generics, used_params, assoc_bounds)
+        Ok(())
+    }
+}
+
+// Render one struct body or enum variant through the matching `Formatter`
+// helper, the way `#[derive(Debug)]` would, or through the struct or variant
+// level format string if there is one. `accesses` holds one place expression
+// per field.
+fn fmt_fields(
+    name: &str,
+    container_attrs: &DebugAttrs,
+    fields: &Fields,
+    accesses: &[TokenStream2],
+    helpers: &mut Helpers,
+) -> syn::Result<TokenStream2> {
+    let forward = container_attrs.forward_flags;
+    let body = match &container_attrs.fmt {
+        Some(fmt) => write_format(fmt, fields, None, accesses, forward)?,
+        None => fmt_entries(name, container_attrs, fields, accesses, helpers)?,
+    };
+    Ok(match &container_attrs.pretty {
+        Some(pretty) => {
+            let pretty = write_format(pretty, fields, None, accesses, forward)?;
+            quote! {
+                if f.alternate() {
+                    #pretty
+                } else {
+                    #body
+                }
+            }
         }
-        Type::Slice(s) => {
-            collect_constraints_excluding_phantom(&s.elem, generics, used_params, assoc_bounds)
+        None => body,
+    })
+}
+
+// The `debug_struct` or `debug_tuple` call printing each field in turn.
+fn fmt_entries(
+    name: &str,
+    container_attrs: &DebugAttrs,
+    fields: &Fields,
+    accesses: &[TokenStream2],
+    helpers: &mut Helpers,
+) -> syn::Result<TokenStream2> {
+    let values = field_values(container_attrs, fields, accesses, helpers)?;
+    let non_exhaustive = values.len() < fields.len();
+    let entries = values.iter().map(|field| {
+        let value = &field.value;
+        match &field.name {
+            Some(name) => quote!(.field(#name, #value)),
+            None => quote!(.field(#value)),
         }
-        Type::Ptr(p) => {
-            collect_constraints_excluding_phantom(&p.elem, generics, used_params, assoc_bounds)
+    });
+    let finish = if non_exhaustive {
+        quote!(.finish_non_exhaustive())
+    } else {
+        quote!(.finish())
+    };
+    Ok(match fields {
+        Fields::Named(_) => quote! {
+            f.debug_struct(#name)
+                #(#entries)*
+                #finish
+        },
+        Fields::Unnamed(_) => quote! {
+            f.debug_tuple(#name)
+                #(#entries)*
+                #finish
+        },
+        Fields::Unit => quote! {
+            f.write_str(#name)
+        },
+    })
+}
+
+// `debug_fields` statements passing each shown field to `visitor`, under
+// its index for tuple fields.
+fn visit_fields(values: &[FieldValue]) -> TokenStream2 {
+    let calls = values.iter().map(|field| {
+        let name = match &field.name {
+            Some(name) => name.clone(),
+            None => field.index.to_string(),
+        };
+        let value = &field.value;
+        quote!(visitor(#name, #value);)
+    });
+    quote!(#(#calls)*)
+}
+
+// A field shown in the `Debug` output, with the name it is shown under and
+// an expression for a reference to the `Debug` value shown for it.
+struct FieldValue {
+    name: Option<String>,
+    index: usize,
+    order: Option<u32>,
+    value: TokenStream2,
+}
+
+// The fields shown by a struct or variant, leaving out skipped ones.
+fn field_values(
+    container_attrs: &DebugAttrs,
+    fields: &Fields,
+    accesses: &[TokenStream2],
+    helpers: &mut Helpers,
+) -> syn::Result<Vec<FieldValue>> {
+    let mut values = Vec::new();
+    for (index, (f, access)) in fields.iter().zip(accesses).enumerate() {
+        // parse #[debug = "format"] or #[debug("format")] via shared helper
+        let mut attrs = extract_debug_attrs(&f.attrs, Position::Field)?;
+        if attrs.skip {
+            continue;
         }
-        Type::Group(g) => {
-            collect_constraints_excluding_phantom(&g.elem, generics, used_params, assoc_bounds)
+        // Struct-level defaults apply to the fields they fit that do not say
+        // how to format themselves.
+        let own_format = attrs.shorthand.is_some()
+            || attrs.fmt.is_some()
+            || attrs.with.is_some()
+            || attrs.max_len.is_some();
+        if let Some(shorthand) = container_attrs.shorthand {
+            if !own_format && shorthand.fits(&f.ty) {
+                attrs.shorthand = Some(shorthand);
+            }
         }
-        Type::Paren(p) => {
-            collect_constraints_excluding_phantom(&p.elem, generics, used_params, assoc_bounds)
+        if let Some(max_len) = container_attrs.max_len {
+            if !own_format && Collection::of(&f.ty).is_some() {
+                attrs.max_len = Some(max_len);
+            }
         }
-        _ => {}
+        let forward = attrs.forward_flags || container_attrs.forward_flags;
+        let value = if let Some(redact) = &attrs.redact {
+            match redact {
+                Redact::Mask(mask) => quote!(&::core::format_args!("{}", #mask)),
+                Redact::Len => quote!(&::core::format_args!("<redacted len={}>", #access.len())),
+            }
+        } else if let Some(with) = &attrs.with {
+            helpers.with = true;
+            quote!(&__DebugWith(&#access, #with))
+        } else if let Some(shorthand) = attrs.shorthand {
+            match shorthand {
+                Shorthand::Hex => quote!(&::core::format_args!("{:#x}", #access)),
+                Shorthand::Bin => quote!(&::core::format_args!("{:#b}", #access)),
+                Shorthand::Octal => quote!(&::core::format_args!("{:#o}", #access)),
+                Shorthand::Bytes => {
+                    helpers.bytes = true;
+                    quote!(&__DebugBytes(&#access[..]))
+                }
+            }
+        } else if let Some(fmt) = attrs.fmt.as_ref().filter(|_| forward) {
+            helpers.fmt_fn = true;
+            let write = write_format(fmt, fields, Some(access), accesses, true)?;
+            quote!(&__DebugFn(|f: &mut ::core::fmt::Formatter<'_>| #write))
+        } else if let Some(fmt) = &attrs.fmt {
+            let fmt = FormatString::parse(fmt, fields, true)?;
+            check_references(&fmt, fields)?;
+            let args = fmt.args(Some(access), accesses);
+            quote!(&::core::format_args!(#args))
+        } else if let Some((max_len, span)) = attrs.max_len {
+            let collection = Collection::of(&f.ty).ok_or_else(|| {
+                syn::Error::new(
+                    span,
+                    "max_len is only supported on slices, arrays, standard collections, String and str",
+                )
+            })?;
+            // The adapters iterate a reference to the collection itself, so
+            // look through any references the field holds it by.
+            let derefs = (0..ref_depth(&f.ty)).map(|_| quote!(*));
+            let access = &quote!((#(#derefs)* #access));
+            match collection {
+                Collection::List | Collection::Set => {
+                    helpers.truncated_list = true;
+                    let set = collection == Collection::Set;
+                    quote!(&__DebugTruncated {
+                        items: &#access,
+                        max_len: #max_len,
+                        set: #set,
+                    })
+                }
+                Collection::Map => {
+                    helpers.truncated_map = true;
+                    quote!(&__DebugTruncatedMap {
+                        entries: &#access,
+                        max_len: #max_len,
+                    })
+                }
+                Collection::Str => {
+                    helpers.truncated_str = true;
+                    quote!(&__DebugTruncatedStr {
+                        text: &#access[..],
+                        max_len: #max_len,
+                    })
+                }
+            }
+        } else if let Some(marker) = marker_summary(&f.ty, &container_attrs.phantom) {
+            marker
+        } else {
+            quote!(&#access)
+        };
+        let value = match attrs.pretty.as_ref().filter(|_| attrs.redact.is_none()) {
+            Some(pretty) => {
+                helpers.fmt_fn = true;
+                let pretty = write_format(pretty, fields, Some(access), accesses, forward)?;
+                quote! {
+                    &__DebugFn(|f: &mut ::core::fmt::Formatter<'_>| {
+                        if f.alternate() {
+                            #pretty
+                        } else {
+                            ::core::fmt::Debug::fmt(#value, f)
+                        }
+                    })
+                }
+            }
+            None => value,
+        };
+        let name =
+            f.ident.as_ref().map(
+                |field_name| match (&attrs.rename, container_attrs.rename_all) {
+                    (Some(rename), _) => rename.value(),
+                    (None, Some(rule)) => rule.apply(&field_name.unraw().to_string()),
+                    (None, None) => field_name.unraw().to_string(),
+                },
+            );
+        values.push(FieldValue {
+            name,
+            index,
+            order: attrs.order,
+            value,
+        });
     }
+    // Fields given an `order` first, by it, then the rest as declared or, with
+    // `sort_fields`, by name.
+    values.sort_by(|a, b| {
+        let rank = |field: &FieldValue| (field.order.is_none(), field.order);
+        let by_name = match (&a.name, &b.name) {
+            (Some(x), Some(y)) if container_attrs.sort_fields && a.order.is_none() => x.cmp(y),
+            _ => std::cmp::Ordering::Equal,
+        };
+        rank(a).cmp(&rank(b)).then(by_name)
+    });
+    Ok(values)
 }
 
-fn handle_type_path(
-    tp: &TypePath,
-    generics: &HashSet<syn::Ident>,
-    used_params: &mut HashSet<syn::Ident>,
-    assoc_bounds: &mut Vec<Type>,
-) {
-    // Ignore PhantomData completely
-    if let Some(seg) = tp.path.segments.last() {
-        if seg.ident == "PhantomData" {
-            return;
-        }
+// An expression writing `fmt` to the formatter `f`. `own` is the field a
+// field-level format belongs to. With `forward`, placeholders without flags of
+// their own take on the flags `f` was given.
+fn write_format(
+    fmt: &syn::LitStr,
+    fields: &Fields,
+    own: Option<&TokenStream2>,
+    accesses: &[TokenStream2],
+    forward: bool,
+) -> syn::Result<TokenStream2> {
+    let fmt = FormatString::parse(fmt, fields, own.is_some())?;
+    check_references(&fmt, fields)?;
+    Ok(if forward {
+        let write = fmt.write(own, accesses);
+        quote!({
+            #write
+            ::core::result::Result::Ok(())
+        })
+    } else {
+        let args = fmt.args(own, accesses);
+        quote!(f.write_fmt(::core::format_args!(#args)))
+    })
+}
+
+// A field whose type is one of the `phantom` markers prints as the marker's
+// name with the names of its type arguments, `Marker<u8>`, the way
+// `PhantomData` prints itself. The marker need not implement `Debug`.
+fn marker_summary(ty: &syn::Type, phantoms: &[syn::Ident]) -> Option<TokenStream2> {
+    let seg = match ty {
+        syn::Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last()?,
+        _ => return None,
+    };
+    if !phantoms.contains(&seg.ident) {
+        return None;
     }
+    let args: Vec<&syn::Type> = match &seg.arguments {
+        syn::PathArguments::AngleBracketed(ab) => ab
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    let mut template = seg.ident.unraw().to_string();
+    if !args.is_empty() {
+        template.push('<');
+        template.push_str(&vec!["{}"; args.len()].join(", "));
+        template.push('>');
+    }
+    Some(quote!(
+        &::core::format_args!(#template #(, ::core::any::type_name::<#args>())*)
+    ))
+}
 
-    // Qualified path like <T as Trait>::Assoc
-    if let Some(q) = &tp.qself {
-        if uses_generic(&q.ty, generics) {
-            assoc_bounds.push(Type::Path(tp.clone()));
+// Parse the comma separated predicates of a `bound = "..."` literal. The
+// tokens are spanned at the literal, so both syntax errors and unresolved
+// names in the predicates point at the attribute that introduced them.
+fn parse_bounds(lit: &syn::LitStr) -> syn::Result<Vec<WherePredicate>> {
+    let preds = lit.parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)?;
+    Ok(preds.into_iter().collect())
+}
+
+// Everything a `#[debug(...)]` attribute can say about a struct or field.
+#[derive(Default)]
+struct DebugAttrs {
+    bounds: Vec<WherePredicate>,
+    fmt: Option<syn::LitStr>,
+    skip: bool,
+    redact: Option<Redact>,
+    with: Option<syn::Path>,
+    shorthand: Option<Shorthand>,
+    rename: Option<syn::LitStr>,
+    rename_all: Option<RenameRule>,
+    // With the span of the `max_len` key, for errors about the field type.
+    max_len: Option<(usize, proc_macro2::Span)>,
+    phantom: Vec<syn::Ident>,
+    pretty: Option<syn::LitStr>,
+    forward_flags: bool,
+    union_tag: Option<syn::LitStr>,
+    order: Option<u32>,
+    sort_fields: bool,
+    // Generate the `debug_fields` method.
+    fields: bool,
+}
+
+enum Redact {
+    // Print the given text in place of the value.
+    Mask(String),
+    // Print only the value's `len()`.
+    Len,
+}
+
+// `#[debug(hex)]`, `#[debug(bin)]`, `#[debug(octal)]` and `#[debug(bytes)]`
+#[derive(Clone, Copy, PartialEq)]
+enum Shorthand {
+    Hex,
+    Bin,
+    Octal,
+    Bytes,
+}
+
+impl Shorthand {
+    fn fmt_trait(self) -> &'static str {
+        match self {
+            Shorthand::Hex => "LowerHex",
+            Shorthand::Bin => "Binary",
+            Shorthand::Octal => "Octal",
+            Shorthand::Bytes => "Debug",
         }
-        return;
     }
 
-    // Bare type parameter like T
-    if tp.path.segments.len() == 1 {
-        let seg = tp.path.segments.last().unwrap();
-        if seg.arguments.is_empty() && generics.contains(&seg.ident) {
-            used_params.insert(seg.ident.clone());
-            return;
+    // Whether a struct-level default applies to a field of this type: integer
+    // shorthands to primitive integers, `bytes` to `u8` buffers.
+    fn fits(self, ty: &syn::Type) -> bool {
+        match self {
+            Shorthand::Bytes => is_byte_buffer(ty),
+            _ => is_integer(ty),
         }
     }
+}
 
-    // Associated type projection like T::Assoc
-    if let Some(first) = tp.path.segments.first() {
-        if generics.contains(&first.ident) && tp.path.segments.len() >= 2 {
-            assoc_bounds.push(Type::Path(tp.clone()));
-            return;
-        }
+fn is_integer(ty: &syn::Type) -> bool {
+    const INTEGERS: &[&str] = &[
+        "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize",
+    ];
+    match ty {
+        syn::Type::Path(tp) if tp.qself.is_none() => match tp.path.segments.last() {
+            Some(seg) => seg.arguments.is_empty() && INTEGERS.iter().any(|int| seg.ident == int),
+            None => false,
+        },
+        syn::Type::Group(g) => is_integer(&g.elem),
+        syn::Type::Paren(p) => is_integer(&p.elem),
+        _ => false,
     }
+}
 
-    // Recurse into generic arguments
-    for seg in &tp.path.segments {
-        match &seg.arguments {
-            PathArguments::AngleBracketed(ab) => {
-                for arg in &ab.args {
-                    if let GenericArgument::Type(t) = arg {
-                        collect_constraints_excluding_phantom(
-                            t,
-                            generics,
-                            used_params,
-                            assoc_bounds,
-                        );
-                    }
-                }
-            }
-            PathArguments::Parenthesized(pb) => {
-                for t in &pb.inputs {
-                    collect_constraints_excluding_phantom(t, generics, used_params, assoc_bounds);
-                }
-            }
-            PathArguments::None => {}
-        }
+// `Vec<u8>`, `[u8; N]`, `[u8]` or a reference to one of them.
+fn is_byte_buffer(ty: &syn::Type) -> bool {
+    let is_u8 = |ty: &syn::Type| matches!(ty, syn::Type::Path(tp) if tp.path.is_ident("u8"));
+    match ty {
+        syn::Type::Array(a) => is_u8(&a.elem),
+        syn::Type::Slice(s) => is_u8(&s.elem),
+        syn::Type::Reference(r) => is_byte_buffer(&r.elem),
+        syn::Type::Path(tp) if tp.qself.is_none() => match tp.path.segments.last() {
+            Some(seg) if seg.ident == "Vec" => match &seg.arguments {
+                syn::PathArguments::AngleBracketed(ab) => matches!(
+                    ab.args.first(),
+                    Some(syn::GenericArgument::Type(elem)) if is_u8(elem)
+                ),
+                _ => false,
+            },
+            _ => false,
+        },
+        _ => false,
     }
 }
 
-fn uses_generic(ty: &Type, generics: &HashSet<syn::Ident>) -> bool {
-    struct Finder<'a> {
-        gens: &'a HashSet<syn::Ident>,
-        found: bool,
+fn ref_depth(ty: &syn::Type) -> usize {
+    match ty {
+        syn::Type::Reference(r) => 1 + ref_depth(&r.elem),
+        syn::Type::Group(g) => ref_depth(&g.elem),
+        syn::Type::Paren(p) => ref_depth(&p.elem),
+        _ => 0,
     }
-    impl<'a> Finder<'a> {
-        fn visit(&mut self, ty: &Type) {
-            match ty {
-                Type::Path(tp) => {
-                    if tp.qself.is_none() && tp.path.segments.len() == 1 {
-                        let seg = tp.path.segments.last().unwrap();
-                        if seg.arguments.is_empty() && self.gens.contains(&seg.ident) {
-                            self.found = true;
-                            return;
-                        }
-                    }
-                    for seg in &tp.path.segments {
-                        if let PathArguments::AngleBracketed(ab) = &seg.arguments {
-                            for arg in &ab.args {
-                                if let GenericArgument::Type(t) = arg {
-                                    self.visit(t);
-                                    if self.found {
-                                        return;
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-                Type::Reference(r) => self.visit(&r.elem),
-                Type::Tuple(t) => {
-                    for e in &t.elems {
-                        self.visit(e);
-                        if self.found {
-                            return;
-                        }
-                    }
+}
+
+// What a `#[debug(max_len = N)]` field is truncated as.
+#[derive(Clone, Copy, PartialEq)]
+enum Collection {
+    List,
+    Set,
+    Map,
+    Str,
+}
+
+impl Collection {
+    // Recognize standard collections and strings by their type name.
+    fn of(ty: &syn::Type) -> Option<Self> {
+        match ty {
+            syn::Type::Array(_) | syn::Type::Slice(_) => Some(Collection::List),
+            syn::Type::Reference(r) => Collection::of(&r.elem),
+            syn::Type::Group(g) => Collection::of(&g.elem),
+            syn::Type::Paren(p) => Collection::of(&p.elem),
+            syn::Type::Path(tp) if tp.qself.is_none() => {
+                let seg = tp.path.segments.last()?;
+                match seg.ident.to_string().as_str() {
+                    "Vec" | "VecDeque" | "LinkedList" | "BinaryHeap" => Some(Collection::List),
+                    "HashSet" | "BTreeSet" => Some(Collection::Set),
+                    "HashMap" | "BTreeMap" => Some(Collection::Map),
+                    "String" | "str" => Some(Collection::Str),
+                    _ => None,
                 }
-                Type::Array(a) => self.visit(&a.elem),
-                Type::Slice(s) => self.visit(&s.elem),
-                Type::Ptr(p) => self.visit(&p.elem),
-                Type::Group(g) => self.visit(&g.elem),
-                Type::Paren(p) => self.visit(&p.elem),
-                _ => {}
             }
+            _ => None,
         }
     }
-    let mut f = Finder {
-        gens: generics,
-        found: false,
-    };
-    f.visit(ty);
-    f.found
 }
 
-fn parse_bounds_str(s: &str) -> Vec<WherePredicate> {
-    let mut preds = Vec::new();
-    for part in s.split(',') {
-        let trimmed = part.trim();
-        if trimmed.is_empty() {
-            continue;
+impl DebugAttrs {
+    // Whether the field's value is never formatted with its `Debug` impl, so
+    // its type needs no `Debug` bound.
+    fn bypasses_debug(&self) -> bool {
+        self.skip || self.redact.is_some() || self.with.is_some()
+    }
+
+    // Take on the enum-level defaults a variant does not set itself.
+    fn inherit(&mut self, parent: &DebugAttrs) {
+        if self.shorthand.is_none() {
+            self.shorthand = parent.shorthand;
+        }
+        if self.max_len.is_none() {
+            self.max_len = parent.max_len;
         }
-        match syn::parse_str::<WherePredicate>(trimmed) {
-            Ok(p) => preds.push(p),
-            Err(_) => {
-                // If parsing fails, ignore silently to keep macro resilient
+        self.phantom.extend(parent.phantom.iter().cloned());
+        self.forward_flags |= parent.forward_flags;
+        self.sort_fields |= parent.sort_fields;
+    }
+}
+
+// Where a `#[debug(...)]` attribute appears. Each key is only accepted where
+// it has an effect.
+#[derive(Clone, Copy, PartialEq)]
+enum Position {
+    Struct,
+    Enum,
+    Variant,
+    Field,
+    Union,
+}
+
+impl Position {
+    // The positions `key` is accepted in, or `None` for an unknown key.
+    fn allowed_for(key: &str) -> Option<&'static [Position]> {
+        use Position::*;
+        Some(match key {
+            "bound" => &[Struct, Enum, Field],
+            "skip" | "redact" | "with" | "order" => &[Field],
+            "hex" | "bin" | "octal" | "bytes" | "max_len" | "forward_flags" => {
+                &[Struct, Enum, Variant, Field]
             }
+            "rename" => &[Struct, Variant, Field, Union],
+            "rename_all" | "sort_fields" => &[Struct, Enum, Variant],
+            "pretty" => &[Struct, Variant, Field],
+            "phantom" | "fields" => &[Struct, Enum],
+            "union_tag" => &[Union],
+            _ => return None,
+        })
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            Position::Struct => "a struct",
+            Position::Enum => "an enum",
+            Position::Variant => "an enum variant",
+            Position::Field => "a field",
+            Position::Union => "a union",
         }
     }
-    preds
 }
 
-fn extract_debug_attrs(attrs: &[Attribute]) -> (Vec<WherePredicate>, Option<String>) {
-    let mut preds = Vec::new();
-    let mut fmt: Option<String> = None;
+fn extract_debug_attrs(attrs: &[Attribute], position: Position) -> syn::Result<DebugAttrs> {
+    let mut out = DebugAttrs::default();
     for attr in attrs {
-        if attr.path().is_ident("debug") {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        if position == Position::Union && !matches!(attr.meta, syn::Meta::List(_)) {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "a format string is not supported on a union",
+            ));
+        }
+        match &attr.meta {
             // NameValue form: #[debug = "..."]
-            if let syn::Meta::NameValue(nv) = &attr.meta {
-                if let syn::Expr::Lit(syn::ExprLit {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(syn::ExprLit {
                     lit: syn::Lit::Str(lit_str),
                     ..
-                }) = &nv.value
-                {
-                    fmt = Some(lit_str.value());
-                    continue;
+                }) => out.fmt = Some(lit_str.clone()),
+                value => {
+                    return Err(syn::Error::new_spanned(
+                        value,
+                        "expected a format string: `#[debug = \"...\"]`",
+                    ))
                 }
+            },
+            // Format string as the sole argument: #[debug("...")]
+            syn::Meta::List(list) if list.parse_args::<syn::LitStr>().is_ok() => {
+                out.fmt = Some(list.parse_args::<syn::LitStr>()?);
             }
-
-            // Nested meta: #[debug(bound = "..." )] or #[debug("...")]
-            let _ = attr.parse_nested_meta(|meta| {
+            // Nested meta: #[debug(bound = "...", skip, ...)]
+            syn::Meta::List(_) => attr.parse_nested_meta(|meta| {
+                let key = meta.path.to_token_stream().to_string().replace(' ', "");
+                if let Some(allowed) = Position::allowed_for(&key) {
+                    if !allowed.contains(&position) {
+                        return Err(meta.error(format_args!(
+                            "`{}` is not supported on {}",
+                            key,
+                            position.describe(),
+                        )));
+                    }
+                }
                 if meta.path.is_ident("bound") {
                     let lit: syn::LitStr = meta.value()?.parse()?;
-                    preds.extend(parse_bounds_str(&lit.value()));
-                } else {
-                    // Try to parse a fmt string like #[debug("...")]
-                    if fmt.is_none() {
-                        if let Ok(_) = meta.value() {
-                            if let Ok(lit) = meta.input.parse::<syn::LitStr>() {
-                                fmt = Some(lit.value());
-                            }
-                        }
+                    out.bounds.extend(parse_bounds(&lit)?);
+                } else if meta.path.is_ident("with") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    out.with = Some(lit.parse()?);
+                } else if meta.path.is_ident("rename") {
+                    out.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("rename_all") {
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    out.rename_all = Some(RenameRule::parse(&lit)?);
+                } else if meta.path.is_ident("pretty") {
+                    out.pretty = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("forward_flags") {
+                    out.forward_flags = true;
+                } else if meta.path.is_ident("order") {
+                    let lit: syn::LitInt = meta.value()?.parse()?;
+                    out.order = Some(lit.base10_parse()?);
+                } else if meta.path.is_ident("sort_fields") {
+                    out.sort_fields = true;
+                } else if meta.path.is_ident("fields") {
+                    out.fields = true;
+                } else if meta.path.is_ident("union_tag") {
+                    out.union_tag = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("max_len") {
+                    let key_span = meta.path.segments[0].ident.span();
+                    let lit: syn::LitInt = meta.value()?.parse()?;
+                    out.max_len = Some((lit.base10_parse()?, key_span));
+                } else if meta.path.is_ident("phantom") {
+                    // #[debug(phantom = "MyMarker, PhantomPinned")], matched
+                    // by the last path segment like PhantomData
+                    let lit: syn::LitStr = meta.value()?.parse()?;
+                    let paths =
+                        lit.parse_with(Punctuated::<syn::Path, Token![,]>::parse_terminated)?;
+                    for path in paths {
+                        let last = path.segments.last().unwrap();
+                        out.phantom.push(last.ident.clone());
                     }
+                } else if meta.path.is_ident("hex") {
+                    out.shorthand = Some(Shorthand::Hex);
+                } else if meta.path.is_ident("bin") {
+                    out.shorthand = Some(Shorthand::Bin);
+                } else if meta.path.is_ident("octal") {
+                    out.shorthand = Some(Shorthand::Octal);
+                } else if meta.path.is_ident("bytes") {
+                    out.shorthand = Some(Shorthand::Bytes);
+                } else if meta.path.is_ident("skip") {
+                    out.skip = true;
+                } else if meta.path.is_ident("redact") {
+                    // #[debug(redact)], #[debug(redact = "len")] or
+                    // #[debug(redact = "mask")]
+                    out.redact = Some(if meta.input.peek(Token![=]) {
+                        let lit: syn::LitStr = meta.value()?.parse()?;
+                        match lit.value().as_str() {
+                            "len" => Redact::Len,
+                            mask => Redact::Mask(mask.to_owned()),
+                        }
+                    } else {
+                        Redact::Mask("<redacted>".to_owned())
+                    });
+                } else {
+                    return Err(meta.error(format_args!("unknown debug attribute `{}`", key)));
                 }
                 Ok(())
-            });
+            })?,
+            syn::Meta::Path(path) => {
+                return Err(syn::Error::new_spanned(
+                    path,
+                    "expected `#[debug = \"...\"]` or `#[debug(...)]`",
+                ))
+            }
         }
     }
-    (preds, fmt)
+    Ok(out)
 }
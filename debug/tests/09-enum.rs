@@ -0,0 +1,47 @@
+// Enums are rendered the way the standard library's derive renders them: a
+// unit variant as its name, a tuple variant through debug_tuple and a variant
+// with named fields through debug_struct, each labelled with the variant name.
+//
+// Field attributes such as #[debug = "..."] apply to variant fields just like
+// to struct fields, and trait bounds are inferred from the fields of every
+// variant together.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub enum Message<T, U> {
+    Quit,
+    Move {
+        x: i32,
+        #[debug = "0b{:08b}"]
+        y: u8,
+    },
+    Write(T, #[debug = "{:?}!"] &'static str),
+    Marker(PhantomData<U>),
+}
+
+#[derive(CustomDebug)]
+pub enum Never {}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Message<u8, NotDebug>>();
+    assert_debug::<Never>();
+
+    let quit: Message<u8, NotDebug> = Message::Quit;
+    assert_eq!(format!("{:?}", quit), "Quit");
+
+    let mv: Message<u8, NotDebug> = Message::Move { x: -1, y: 5 };
+    assert_eq!(format!("{:?}", mv), "Move { x: -1, y: 0b00000101 }");
+
+    let write: Message<u8, NotDebug> = Message::Write(7, "hi");
+    assert_eq!(format!("{:?}", write), r#"Write(7, "hi"!)"#);
+
+    let marker: Message<u8, NotDebug> = Message::Marker(PhantomData);
+    assert!(format!("{:?}", marker).starts_with("Marker(PhantomData<"));
+}
@@ -0,0 +1,24 @@
+// Tuple structs are rendered through debug_tuple, accessing their fields by
+// index, and unit structs are rendered as just their name, matching the
+// standard library's derive. Field attributes such as #[debug = "..."] work on
+// tuple struct fields too.
+
+use derive_debug::CustomDebug;
+use std::marker::PhantomData;
+
+#[derive(CustomDebug)]
+pub struct Meters<T>(T, #[debug = "0x{:02x}"] u8, PhantomData<T>);
+
+#[derive(CustomDebug)]
+pub struct Unit;
+
+#[derive(CustomDebug)]
+pub struct Empty();
+
+fn main() {
+    let m = Meters(1.5, 255, PhantomData);
+    assert_eq!(format!("{:?}", m), "Meters(1.5, 0xff, PhantomData<f64>)");
+
+    assert_eq!(format!("{:?}", Unit), "Unit");
+    assert_eq!(format!("{:?}", Empty()), "Empty");
+}
@@ -0,0 +1,49 @@
+// Secrets must never end up in logs. A field marked #[debug(skip)] is left out
+// of the output entirely, which is signalled with a trailing `..` as printed by
+// Formatter's finish_non_exhaustive. A field marked #[debug(redact)] is printed
+// as <redacted>, #[debug(redact = "...")] prints the given mask instead, and
+// #[debug(redact = "len")] prints only the length of the value.
+//
+// Since the Debug impl of a skipped or redacted field is never called, its
+// type must not receive an inferred Debug bound.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Credentials<K, S> {
+    user: &'static str,
+    #[debug(redact)]
+    password: String,
+    #[debug(redact = "****")]
+    pin: u16,
+    #[debug(redact = "len")]
+    token: Vec<S>,
+    #[debug(skip)]
+    key: K,
+}
+
+#[derive(CustomDebug)]
+pub struct Session(u32, #[debug(skip)] String);
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Credentials<NotDebug, NotDebug>>();
+
+    let creds = Credentials {
+        user: "root",
+        password: "hunter2".to_owned(),
+        pin: 1234,
+        token: vec![NotDebug, NotDebug, NotDebug],
+        key: NotDebug,
+    };
+    let debug = format!("{:?}", creds);
+    let expected = r#"Credentials { user: "root", password: <redacted>, pin: ****, token: <redacted len=3>, .. }"#;
+    assert_eq!(debug, expected);
+
+    let session = Session(7, "secret".to_owned());
+    assert_eq!(format!("{:?}", session), "Session(7, ..)");
+}
@@ -0,0 +1,74 @@
+// A format string is not always enough to render a field well. A field
+// attribute #[debug(with = "path::to::function")] names a function with the
+// signature
+//
+//     fn(&FieldType, &mut fmt::Formatter) -> fmt::Result
+//
+// which is called to render the field in place of its Debug impl. The field's
+// type therefore does not need to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::Duration;
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "render::hex")]
+    payload: Vec<u8>,
+    #[debug(with = "render::millis")]
+    elapsed: Duration,
+    #[debug(with = "render::sorted")]
+    headers: HashMap<&'static str, &'static str>,
+    #[debug(with = "render::opaque")]
+    extra: T,
+}
+
+mod render {
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::time::Duration;
+
+    pub fn hex(bytes: &Vec<u8>, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+
+    pub fn millis(d: &Duration, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}ms", d.as_millis())
+    }
+
+    pub fn sorted(map: &HashMap<&'static str, &'static str>, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut entries: Vec<_> = map.iter().collect();
+        entries.sort();
+        f.debug_map().entries(entries).finish()
+    }
+
+    pub fn opaque<T>(_: &T, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("_")
+    }
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Packet<NotDebug>>();
+
+    let mut headers = HashMap::new();
+    headers.insert("b", "2");
+    headers.insert("a", "1");
+    let packet = Packet {
+        payload: vec![0x0a, 0x1b, 0xff],
+        elapsed: Duration::from_millis(1500),
+        headers,
+        extra: NotDebug,
+    };
+
+    let debug = format!("{:?}", packet);
+    let expected = r#"Packet { payload: 0a1bff, elapsed: 1500ms, headers: {"a": "1", "b": "2"}, extra: _ }"#;
+    assert_eq!(debug, expected);
+}
@@ -0,0 +1,13 @@
+// A misspelled key inside #[debug(...)] must not be silently ignored, since
+// that would quietly drop whatever the caller meant to configure. Report it as
+// an error pointing at the offending key.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    #[debug(bond = "T: Debug")]
+    name: &'static str,
+}
+
+fn main() {}
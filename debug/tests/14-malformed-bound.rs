@@ -0,0 +1,17 @@
+// Bounds given through #[debug(bound = "...")] are parsed as where-predicates.
+// A predicate that fails to parse is reported at the string literal rather
+// than being silently dropped.
+
+use derive_debug::CustomDebug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value Debug")]
+pub struct Unparsable<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+fn main() {}
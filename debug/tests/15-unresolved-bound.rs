@@ -0,0 +1,17 @@
+// A bound that parses but names a trait that does not exist is reported by the
+// compiler at the string literal it came from, because the predicate tokens
+// carry the span of that literal.
+
+use derive_debug::CustomDebug;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "T::Value: Debg")]
+pub struct Misspelled<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+fn main() {}
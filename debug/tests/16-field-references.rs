@@ -0,0 +1,117 @@
+// Format strings may refer to other fields of the same struct or variant.
+//
+// A struct-level #[debug("...")] renders the whole struct through that single
+// format string, where fields are referred to as {self.host}, {self.0} or just
+// {host}. A field-level format may combine its own value, written {} or {0},
+// with other fields referred to by name. References are checked against the
+// field list at compile time.
+//
+// Inferred bounds follow the placeholders: a field referred to as {host} needs
+// Display, as {host:?} Debug, and a struct or variant format asks nothing of
+// the fields it does not mention.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug, Display};
+
+#[derive(CustomDebug)]
+#[debug("{self.host}:{self.port}")]
+pub struct Addr {
+    host: &'static str,
+    port: u16,
+}
+
+#[derive(CustomDebug)]
+#[debug("#{self.0:03} ({self.1:?})")]
+pub struct Tagged(u32, &'static str);
+
+#[derive(CustomDebug)]
+pub struct Progress {
+    #[debug("{0}/{total} ({unit})")]
+    done: u32,
+    total: u32,
+    unit: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub enum Shape {
+    #[debug("{w}x{h}")]
+    Rect { w: u32, h: u32 },
+    Circle(#[debug("r={}")] u32),
+}
+
+#[derive(CustomDebug)]
+#[debug("{self.host}:{self.port}")]
+pub struct GenericAddr<H, M> {
+    host: H,
+    port: u16,
+    meta: M,
+}
+
+#[derive(CustomDebug)]
+pub struct Counter<T> {
+    #[debug("{0}/{total}")]
+    done: u32,
+    #[debug("{}")]
+    total: T,
+}
+
+#[derive(CustomDebug)]
+pub enum Labeled<T, M> {
+    #[debug("<{0}>")]
+    Label(T, M),
+}
+
+// Implements Display but not Debug.
+pub struct Name(&'static str);
+
+impl Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+// Implements neither.
+pub struct Opaque;
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_debug::<GenericAddr<Name, Opaque>>();
+    assert_debug::<Counter<Name>>();
+    assert_debug::<Labeled<Name, Opaque>>();
+
+    let addr = Addr {
+        host: "localhost",
+        port: 8080,
+    };
+    assert_eq!(format!("{:?}", addr), "localhost:8080");
+
+    assert_eq!(format!("{:?}", Tagged(7, "x")), r#"#007 ("x")"#);
+
+    let progress = Progress {
+        done: 3,
+        total: 10,
+        unit: "files",
+    };
+    let expected = r#"Progress { done: 3/10 (files), total: 10, unit: "files" }"#;
+    assert_eq!(format!("{:?}", progress), expected);
+
+    assert_eq!(format!("{:?}", Shape::Rect { w: 2, h: 3 }), "2x3");
+    assert_eq!(format!("{:?}", Shape::Circle(4)), "Circle(r=4)");
+
+    let addr = GenericAddr {
+        host: Name("example.com"),
+        port: 443,
+        meta: Opaque,
+    };
+    assert_eq!(format!("{:?}", addr), "example.com:443");
+
+    let counter = Counter {
+        done: 1,
+        total: Name("many"),
+    };
+    assert_eq!(format!("{:?}", counter), "Counter { done: 1/many, total: many }");
+
+    let labeled = Labeled::Label(Name("x"), Opaque);
+    assert_eq!(format!("{:?}", labeled), "<x>");
+}
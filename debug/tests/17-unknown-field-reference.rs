@@ -0,0 +1,13 @@
+// A format string referring to a field that does not exist is rejected at
+// compile time, pointing at the format string.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug("{self.host}:{self.prot}")]
+pub struct Addr {
+    host: &'static str,
+    port: u16,
+}
+
+fn main() {}
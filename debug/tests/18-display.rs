@@ -0,0 +1,68 @@
+// Display impls are often just a format string over the fields. The
+// CustomDisplay derive from this crate generates one from a struct-level
+// #[display("...")] attribute, or from one such attribute on every variant of
+// an enum, using the same field references as #[debug("...")].
+//
+// Bounds are inferred like for CustomDebug, except that only the fields the
+// format string refers to are considered, each bounded by the trait its
+// placeholder selects: Display for {x}, Debug for {x:?}, LowerHex for {x:x}
+// and so on.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::Display;
+
+#[derive(CustomDisplay, CustomDebug)]
+#[display("{self.host}:{self.port}")]
+pub struct Addr {
+    host: &'static str,
+    port: u16,
+}
+
+#[derive(CustomDisplay)]
+#[display("{first} and {second:?}")]
+pub struct Pair<T, U> {
+    first: T,
+    second: U,
+}
+
+#[derive(CustomDisplay)]
+#[display("0x{self.0:04x}")]
+pub struct Hidden<T, U>(T, U);
+
+#[derive(CustomDisplay)]
+pub enum Status {
+    #[display("ok")]
+    Ok,
+    #[display("failed with code {0}")]
+    Failed(i32),
+    #[display("retrying in {secs}s")]
+    Retry { secs: u64 },
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    struct NotDisplay;
+
+    assert_display::<Pair<u8, Vec<u8>>>();
+    assert_display::<Hidden<u16, NotDisplay>>();
+
+    let addr = Addr {
+        host: "localhost",
+        port: 8080,
+    };
+    assert_eq!(addr.to_string(), "localhost:8080");
+    assert_eq!(format!("{:?}", addr), r#"Addr { host: "localhost", port: 8080 }"#);
+
+    let pair = Pair {
+        first: 1,
+        second: vec![2, 3],
+    };
+    assert_eq!(pair.to_string(), "1 and [2, 3]");
+
+    assert_eq!(Hidden(255u16, NotDisplay).to_string(), "0x00ff");
+
+    assert_eq!(Status::Ok.to_string(), "ok");
+    assert_eq!(Status::Failed(2).to_string(), "failed with code 2");
+    assert_eq!(Status::Retry { secs: 5 }.to_string(), "retrying in 5s");
+}
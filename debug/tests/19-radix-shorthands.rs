@@ -0,0 +1,82 @@
+// Register-like structs read best with integers in a fixed radix. The field
+// attributes #[debug(hex)], #[debug(bin)] and #[debug(octal)] print an integer
+// as 0x1c, 0b11100 or 0o34, and #[debug(bytes)] prints a u8 buffer such as a
+// Vec<u8> or [u8; N] as space separated hex bytes.
+//
+// On the struct, the same attributes set a default for every field they fit:
+// the integer shorthands apply to primitive integer fields and `bytes` to u8
+// buffers. Fields with their own format are left alone.
+
+use derive_debug::CustomDebug;
+use std::fmt::{Debug, LowerHex};
+
+#[derive(CustomDebug)]
+#[debug(hex)]
+pub struct Register {
+    addr: u32,
+    #[debug(bin)]
+    flags: u8,
+    #[debug = "{}"]
+    count: u16,
+    #[debug(octal)]
+    mode: u16,
+    name: &'static str,
+}
+
+#[derive(CustomDebug)]
+pub struct Frame<T> {
+    #[debug(bytes)]
+    payload: Vec<u8>,
+    #[debug(bytes)]
+    header: [u8; 2],
+    #[debug(hex)]
+    id: T,
+}
+
+#[derive(CustomDebug)]
+#[debug(hex)]
+pub enum Access {
+    Read(u8),
+    Write { addr: u16, value: u8 },
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Formatted through LowerHex rather than Debug.
+    #[derive(Clone, Copy)]
+    struct Id(u8);
+
+    impl LowerHex for Id {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            LowerHex::fmt(&self.0, f)
+        }
+    }
+
+    assert_debug::<Frame<Id>>();
+
+    let reg = Register {
+        addr: 0x4000_1000,
+        flags: 0b101,
+        count: 12,
+        mode: 0o644,
+        name: "CTRL",
+    };
+    let expected = r#"Register { addr: 0x40001000, flags: 0b101, count: 12, mode: 0o644, name: "CTRL" }"#;
+    assert_eq!(format!("{:?}", reg), expected);
+
+    let frame = Frame {
+        payload: vec![0x0a, 0x1b, 0xff],
+        header: [0, 1],
+        id: Id(0x2a),
+    };
+    let expected = "Frame { payload: 0a 1b ff, header: 00 01, id: 0x2a }";
+    assert_eq!(format!("{:?}", frame), expected);
+
+    assert_eq!(format!("{:?}", Access::Read(255)), "Read(0xff)");
+    let write = Access::Write {
+        addr: 0x10,
+        value: 1,
+    };
+    assert_eq!(format!("{:?}", write), "Write { addr: 0x10, value: 0x1 }");
+}
@@ -0,0 +1,45 @@
+// The names printed in the Debug output can be changed to match names used
+// elsewhere, such as wire field names that operations tooling searches for.
+//
+// #[debug(rename = "...")] replaces the name of a struct, variant or field.
+// #[debug(rename_all = "...")] on a struct or variant applies a casing rule to
+// all of its field names, and on an enum to all of its variant names, using
+// the same rule names as serde: "lowercase", "UPPERCASE", "PascalCase",
+// "camelCase", "snake_case", "SCREAMING_SNAKE_CASE", "kebab-case" and
+// "SCREAMING-KEBAB-CASE".
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(rename = "Request", rename_all = "camelCase")]
+pub struct HttpRequest {
+    request_id: u32,
+    #[debug(rename = "X-Trace")]
+    trace_id: u32,
+    r#type: &'static str,
+}
+
+#[derive(CustomDebug)]
+#[debug(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Event {
+    LoggedIn,
+    #[debug(rename_all = "kebab-case")]
+    PageViewed { page_url: &'static str },
+    #[debug(rename = "bye")]
+    LoggedOut,
+}
+
+fn main() {
+    let req = HttpRequest {
+        request_id: 1,
+        trace_id: 2,
+        r#type: "GET",
+    };
+    let expected = r#"Request { requestId: 1, X-Trace: 2, type: "GET" }"#;
+    assert_eq!(format!("{:?}", req), expected);
+
+    assert_eq!(format!("{:?}", Event::LoggedIn), "LOGGED_IN");
+    let viewed = Event::PageViewed { page_url: "/" };
+    assert_eq!(format!("{:?}", viewed), r#"PAGE_VIEWED { page-url: "/" }"#);
+    assert_eq!(format!("{:?}", Event::LoggedOut), "bye");
+}
@@ -0,0 +1,77 @@
+// A struct holding a large buffer or a long log line makes for unreadable
+// Debug output. #[debug(max_len = N)] on a collection or string field prints
+// only its first N items or characters followed by `... (M more)`. Lists and
+// slices keep their brackets, sets and maps their braces.
+//
+// On the struct, #[debug(max_len = N)] sets a default for every field whose
+// type is a standard collection or string: Vec, VecDeque, arrays, slices, the
+// std sets and maps, String and str. Fields with their own format are left
+// alone.
+//
+// Truncation asks nothing more of the items than the Debug impl that would
+// print them in full.
+
+use derive_debug::CustomDebug;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Batch<T> {
+    #[debug(max_len = 3)]
+    items: Vec<T>,
+    #[debug(max_len = 5)]
+    label: String,
+    total: usize,
+}
+
+#[derive(CustomDebug)]
+#[debug(max_len = 2)]
+pub struct Index<'a> {
+    ids: BTreeSet<u32>,
+    names: BTreeMap<&'static str, u32>,
+    raw: &'a [u8],
+    #[debug = "{:?}"]
+    all: Vec<u8>,
+    short: String,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Only Debug, not Clone or Display.
+    #[derive(Debug)]
+    struct Item;
+
+    assert_debug::<Batch<Item>>();
+
+    let batch = Batch {
+        items: vec![1, 2, 3, 4, 5],
+        label: "truncated".to_owned(),
+        total: 5,
+    };
+    let expected = r#"Batch { items: [1, 2, 3, ... (2 more)], label: "trunc"... (4 more), total: 5 }"#;
+    assert_eq!(format!("{:?}", batch), expected);
+
+    let short = Batch {
+        items: vec![1, 2, 3],
+        label: "short".to_owned(),
+        total: 3,
+    };
+    let expected = r#"Batch { items: [1, 2, 3], label: "short", total: 3 }"#;
+    assert_eq!(format!("{:?}", short), expected);
+
+    let index = Index {
+        ids: (1..=4).collect(),
+        names: [("a", 1), ("b", 2), ("c", 3)].into_iter().collect(),
+        raw: &[9, 8, 7],
+        all: vec![1, 2, 3],
+        short: "ok".to_owned(),
+    };
+    let expected = concat!(
+        "Index { ids: {1, 2, ... (2 more)}, ",
+        r#"names: {"a": 1, "b": 2, ... (1 more)}, "#,
+        "raw: [9, 8, ... (1 more)], all: [1, 2, 3], ",
+        r#"short: "ok" }"#,
+    );
+    assert_eq!(format!("{:?}", index), expected);
+}
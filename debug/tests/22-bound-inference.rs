@@ -0,0 +1,71 @@
+// Inferred bounds follow the shape of each field type, so that the escape
+// hatch from 08-escape-hatch.rs is needed less often.
+//
+// Raw pointers and fn pointers are Debug whatever they point to or take, and
+// need no bound on their type parameters at all. A trait object or macro type
+// does not say which of its type parameters matter, so when one mentions a
+// type parameter the whole field type is bounded instead:
+//
+//     impl<'a, T, U, V, W> Debug for Scene<'a, T, U, V, W>
+//     where
+//         Vec<Box<dyn Shape<T>>>: Debug,
+//         id!(W): Debug,
+//     {...}
+//
+// Here that bound holds for every T because Debug is a supertrait of Shape.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+pub trait Shape<T>: Debug {
+    fn area(&self) -> T;
+}
+
+macro_rules! id {
+    ($t:ty) => {
+        $t
+    };
+}
+
+#[derive(CustomDebug)]
+pub struct Scene<'a, T, U, V, W> {
+    shapes: Vec<Box<dyn Shape<T>>>,
+    #[debug(skip)]
+    on_draw: fn(U) -> U,
+    origin: *const V,
+    name: &'a str,
+    scale: id!(W),
+}
+
+#[derive(Debug)]
+struct Square(u8);
+
+impl Shape<Area> for Square {
+    fn area(&self) -> Area {
+        Area(self.0 * self.0)
+    }
+}
+
+fn assert_debug<F: Debug>() {}
+
+// Not Debug.
+struct Area(#[allow(dead_code)] u8);
+
+fn main() {
+    assert_debug::<Scene<Area, Area, Area, u8>>();
+
+    fn same(area: Area) -> Area {
+        area
+    }
+
+    let scene = Scene::<Area, Area, Area, u8> {
+        shapes: vec![Box::new(Square(2))],
+        on_draw: same,
+        origin: std::ptr::null(),
+        name: "plan",
+        scale: 3,
+    };
+    let expected = r#"Scene { shapes: [Square(2)], origin: 0x0, name: "plan", scale: 3, .. }"#;
+    assert_eq!(format!("{:?}", scene), expected);
+    assert_eq!((scene.on_draw)(scene.shapes[0].area()).0, 4);
+}
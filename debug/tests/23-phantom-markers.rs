@@ -0,0 +1,51 @@
+// Codebases with their own zero-sized marker types want them treated like
+// PhantomData. The struct-level #[debug(phantom = "...")] attribute names
+// such types, matched by their last path segment the same way PhantomData is.
+//
+// A marker field adds no bounds on the type parameters it mentions, and is
+// printed as the marker's name with the names of its type arguments, as in
+// `Unit<u8>`, without needing the marker to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::{PhantomData, PhantomPinned};
+
+pub struct Unit<T>(PhantomData<T>);
+
+#[derive(CustomDebug)]
+#[debug(phantom = "Unit, std::marker::PhantomPinned")]
+pub struct Length<T, U> {
+    value: f64,
+    unit: Unit<T>,
+    pinned: PhantomPinned,
+    source: PhantomData<U>,
+}
+
+#[derive(CustomDebug)]
+#[debug(phantom = "Unit")]
+pub enum Quantity<T> {
+    Known(f64, Unit<T>),
+    Unknown,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct Meters;
+
+    assert_debug::<Length<Meters, Meters>>();
+    assert_debug::<Quantity<Meters>>();
+
+    let length = Length::<u8, u16> {
+        value: 1.5,
+        unit: Unit(PhantomData),
+        pinned: PhantomPinned,
+        source: PhantomData,
+    };
+    let expected = "Length { value: 1.5, unit: Unit<u8>, pinned: PhantomPinned, source: PhantomData<u16> }";
+    assert_eq!(format!("{:?}", length), expected);
+
+    let known = Quantity::<u8>::Known(2.0, Unit(PhantomData));
+    assert_eq!(format!("{:?}", known), "Known(2.0, Unit<u8>)");
+}
@@ -0,0 +1,66 @@
+// Formats written for single-line output read badly in the multi-line `{:#?}`
+// form, and a format string normally drops the width and precision the
+// caller asked for.
+//
+// #[debug(pretty = "...")] on a struct, variant or field gives a second
+// format used instead when the formatter is in alternate mode. It accepts
+// everything #[debug = "..."] does.
+//
+// #[debug(forward_flags)] on a struct, variant or field makes the
+// placeholders of its formats that set no flags of their own, such as `{}` or
+// `{:?}`, use the flags of the outer formatter, the way fields printed
+// without a format already do.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Point {
+    #[debug = "x={}"]
+    #[debug(forward_flags)]
+    x: f64,
+    #[debug("y={:?}")]
+    #[debug(forward_flags)]
+    y: f64,
+    #[debug = "({:.1})"]
+    z: f64,
+}
+
+#[derive(CustomDebug)]
+#[debug("{self.w}x{self.h}")]
+#[debug(pretty = "width: {self.w}\nheight: {self.h}")]
+pub struct Size {
+    w: u32,
+    h: u32,
+}
+
+#[derive(CustomDebug)]
+pub struct Query {
+    #[debug = "{}"]
+    #[debug(pretty = "\n    {}\n")]
+    sql: &'static str,
+    #[debug(pretty = "{} rows")]
+    limit: u32,
+}
+
+fn main() {
+    let p = Point {
+        x: 1.0,
+        y: 2.25,
+        z: 3.0,
+    };
+    assert_eq!(format!("{:?}", p), "Point { x: x=1, y: y=2.25, z: (3.0) }");
+    let expected = "Point { x: x=1.00, y: y=2.25, z: (3.0) }";
+    assert_eq!(format!("{:.2?}", p), expected);
+
+    let size = Size { w: 3, h: 4 };
+    assert_eq!(format!("{:?}", size), "3x4");
+    assert_eq!(format!("{:#?}", size), "width: 3\nheight: 4");
+
+    let q = Query {
+        sql: "SELECT 1",
+        limit: 10,
+    };
+    assert_eq!(format!("{:?}", q), "Query { sql: SELECT 1, limit: 10 }");
+    let expected = "Query {\n    sql: \n        SELECT 1\n    ,\n    limit: 10 rows,\n}";
+    assert_eq!(format!("{:#?}", q), expected);
+}
@@ -0,0 +1,61 @@
+// FFI bindings wrap C unions, and a union keeps no record of which field was
+// last written, so its fields cannot safely be read to print them.
+//
+// A #[repr(C)] union whose fields are all integers, floats or arrays of them
+// prints as the bytes that every field covers, which are initialized
+// whichever field was written. #[debug(union_tag = "...")] on such a union
+// instead prints one chosen field, typically a discriminant at the start of
+// every C variant. That field must be no larger than any other field. Any
+// other union prints as `Name { .. }`.
+//
+// The fields of #[repr(packed)] structs cannot be borrowed, so they are
+// formatted from copies, as the built-in derive does.
+
+use derive_debug::CustomDebug;
+use std::mem::ManuallyDrop;
+
+#[derive(CustomDebug)]
+#[repr(C)]
+pub union Value {
+    i: u32,
+    f: f32,
+    c: std::ffi::c_int,
+    b: [u8; 2],
+}
+
+#[derive(CustomDebug)]
+#[repr(C)]
+#[debug(union_tag = "kind")]
+pub union Event {
+    kind: u8,
+    key: [u8; 4],
+    mouse: [u16; 2],
+}
+
+#[derive(CustomDebug)]
+pub union Opaque {
+    n: u32,
+    s: ManuallyDrop<String>,
+}
+
+#[derive(CustomDebug)]
+#[repr(C, packed)]
+pub struct Header {
+    tag: u8,
+    #[debug = "{:#x}"]
+    len: u32,
+}
+
+fn main() {
+    let value = Value { b: [1, 2] };
+    assert_eq!(format!("{:?}", value), "Value(01 02)");
+
+    let event = Event { key: [3, 0, 0, 0] };
+    assert_eq!(format!("{:?}", event), "Event { kind: 3, .. }");
+
+    let opaque = Opaque { n: 1 };
+    assert_eq!(format!("{:?}", opaque), "Opaque { .. }");
+
+    let header = Header { tag: 1, len: 64 };
+    assert_eq!(format!("{:?}", header), "Header { tag: 1, len: 0x40 }");
+}
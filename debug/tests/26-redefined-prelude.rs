@@ -0,0 +1,117 @@
+// As with the builder's 09-redefined-prelude-types.rs, the generated Debug
+// impl must keep working when the caller's code gives standard names a
+// different meaning: prelude types and variants, primitive type names, and
+// the standard formatting macros, which a `macro_rules!` definition shadows
+// for everything expanded after it.
+//
+// Everything in the expanded code is referred to by absolute path, such as
+// ::core::format_args! and ::core::primitive::usize.
+
+#![allow(non_camel_case_types, unused_macros)]
+
+use derive_debug::CustomDebug;
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+type Option = ();
+type Some = ();
+type None = ();
+type Result = ();
+type Ok = ();
+type Err = ();
+type usize = ();
+type str = ();
+type bool = ();
+
+macro_rules! format_args {
+    ($($tt:tt)*) => {
+        compile_error!("expanded code used the caller's format_args!")
+    };
+}
+
+macro_rules! write {
+    ($($tt:tt)*) => {
+        compile_error!("expanded code used the caller's write!")
+    };
+}
+
+macro_rules! stringify {
+    ($($tt:tt)*) => {
+        compile_error!("expanded code used the caller's stringify!")
+    };
+}
+
+fn show(n: &u8, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    f.write_str(if *n == 0 { "zero" } else { "nonzero" })
+}
+
+#[derive(CustomDebug)]
+#[debug(forward_flags, fields)]
+pub struct Packet {
+    #[debug = "#{}"]
+    id: u8,
+    #[debug(hex)]
+    flags: u8,
+    #[debug(bytes)]
+    payload: Vec<u8>,
+    #[debug(max_len = 2)]
+    items: Vec<u8>,
+    #[debug(max_len = 2)]
+    name: String,
+    #[debug(max_len = 2)]
+    headers: BTreeMap<u8, u8>,
+    #[debug(with = "show")]
+    count: u8,
+    #[debug(redact = "len")]
+    secret: String,
+    #[debug(pretty = "{} (pretty)")]
+    tag: u8,
+}
+
+#[derive(CustomDebug)]
+#[debug("{self.0}..{self.1}")]
+pub struct Span(u8, u8);
+
+pub struct Marker<T>(PhantomData<T>);
+
+#[derive(CustomDebug)]
+#[debug(phantom = "Marker", fields)]
+pub enum Tagged {
+    Unit,
+    Marked(Marker<u8>),
+    Named {
+        #[debug(order = 0)]
+        b: u8,
+        a: u8,
+    },
+}
+
+#[derive(CustomDebug)]
+#[repr(C)]
+pub union Bytes {
+    n: u32,
+    b: [u8; 2],
+}
+
+#[derive(CustomDebug)]
+#[repr(C)]
+#[debug(union_tag = "kind")]
+pub union Tag {
+    kind: u8,
+    n: u32,
+}
+
+#[derive(CustomDebug)]
+pub union Opaque {
+    n: u32,
+    p: *const u8,
+}
+
+#[derive(CustomDebug)]
+#[repr(C, packed)]
+pub struct Packed {
+    a: u8,
+    b: u32,
+}
+
+fn main() {}
@@ -0,0 +1,76 @@
+// Structured loggers want fields as key/value pairs rather than one Debug
+// string. With #[debug(fields)] on the struct or enum, the derive also
+// generates an inherent method
+//
+//     pub fn debug_fields(&self, visitor: &mut impl FnMut(&'static str, &dyn Debug))
+//
+// that calls the visitor once for every field the Debug output shows, in the
+// same order, with the name and value it shows them with. Skipped fields are
+// left out, renames apply, redacted fields pass their mask, and tuple fields
+// are named by their index.
+//
+// Without #[debug(fields)] no method is generated, so a type is free to have a
+// `debug_fields` method of its own.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+#[debug(fields, rename_all = "camelCase")]
+pub struct Login {
+    user_name: String,
+    #[debug(redact)]
+    password: String,
+    #[debug(skip)]
+    attempts: u32,
+    #[debug(rename = "ip", hex)]
+    remote_addr: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug(fields)]
+pub enum Event {
+    Click(i32, i32),
+    Key { code: u8 },
+    Idle,
+}
+
+#[derive(CustomDebug)]
+pub struct Plain {
+    value: u8,
+}
+
+impl Plain {
+    pub fn debug_fields(&self) -> &'static str {
+        "own method"
+    }
+}
+
+fn pairs(event: &Event) -> Vec<String> {
+    let mut out = Vec::new();
+    event.debug_fields(&mut |name: &'static str, value: &dyn Debug| {
+        out.push(format!("{}={:?}", name, value));
+    });
+    out
+}
+
+fn main() {
+    let login = Login {
+        user_name: "ferris".to_owned(),
+        password: "hunter2".to_owned(),
+        attempts: 3,
+        remote_addr: 0x7f000001,
+    };
+    let mut fields = Vec::new();
+    login.debug_fields(&mut |name, value| fields.push(format!("{}={:?}", name, value)));
+    assert_eq!(fields, ["userName=\"ferris\"", "password=<redacted>", "ip=0x7f000001"]);
+    assert_eq!(login.attempts, 3);
+
+    assert_eq!(pairs(&Event::Click(3, -4)), ["0=3", "1=-4"]);
+    assert_eq!(pairs(&Event::Key { code: 13 }), ["code=13"]);
+    assert!(pairs(&Event::Idle).is_empty());
+
+    let plain = Plain { value: 1 };
+    assert_eq!(plain.debug_fields(), "own method");
+    assert_eq!(format!("{:?}", plain), "Plain { value: 1 }");
+}
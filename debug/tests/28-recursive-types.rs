@@ -0,0 +1,149 @@
+// A field whose type refers back to the type being derived, by name or as
+// `Self`, adds no bounds of its own: its Debug impl is the one being
+// generated, which holds whenever the bounds from the other fields do.
+// Bounding it anyway would either ask more than needed, as `T: Debug` below
+// when only `T::Value` is printed, or make the impl depend on itself:
+//
+//     impl<T: Debug> Debug for Chain<T>
+//     where
+//         link!(Chain<T>): Debug,
+//     {...}
+//
+// which overflows the compiler's trait solver the first time the impl is
+// used. Types that are only recursive together with another type still get
+// the bounds their fields need, just as with the built-in derive.
+//
+// Only `Self` and the type's own plain name count as referring back to it. A
+// type of the same name from another module, like `other::Node<T>` inside
+// `Node<T>`, is an ordinary field type and bounds `T` as usual. A projection
+// through the type itself, like `<Projected<T> as Trait>::Value`, is not
+// recursive either, and gets a where-clause bound like any other projection.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct List<T: Trait> {
+    value: T::Value,
+    next: Option<Box<List<T>>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Tree<T> {
+    value: T,
+    children: Vec<Self>,
+}
+
+macro_rules! link {
+    ($t:ty) => {
+        Option<Box<$t>>
+    };
+}
+
+#[derive(CustomDebug)]
+pub struct Chain<T> {
+    value: T,
+    next: link!(Chain<T>),
+}
+
+#[derive(CustomDebug)]
+pub enum Expr<T> {
+    Lit(T),
+    Block(Box<Block<T>>),
+}
+
+#[derive(CustomDebug)]
+pub struct Block<T> {
+    exprs: Vec<Expr<T>>,
+}
+
+mod other {
+    #[derive(Debug)]
+    pub struct Node<T>(pub T);
+}
+
+#[derive(CustomDebug)]
+pub struct Node<T> {
+    inner: other::Node<T>,
+    linked: link!(other::Node<T>),
+}
+
+#[derive(CustomDebug)]
+pub struct Projected<T> {
+    value: <Projected<T> as Trait>::Value,
+    marker: PhantomData<T>,
+}
+
+impl<T> Trait for Projected<T> {
+    type Value = T;
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    // Does not implement Debug.
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    assert_debug::<List<Id>>();
+    assert_debug::<Tree<u8>>();
+    assert_debug::<Chain<u8>>();
+    assert_debug::<Expr<u8>>();
+    assert_debug::<Block<u8>>();
+    assert_debug::<Node<u8>>();
+    assert_debug::<Projected<u8>>();
+
+    let list = List::<Id> {
+        value: 1,
+        next: Some(Box::new(List {
+            value: 2,
+            next: None,
+        })),
+    };
+    let expected = "List { value: 1, next: Some(List { value: 2, next: None }) }";
+    assert_eq!(format!("{:?}", list), expected);
+
+    let chain = Chain {
+        value: 'a',
+        next: Some(Box::new(Chain {
+            value: 'b',
+            next: None,
+        })),
+    };
+    let expected = "Chain { value: 'a', next: Some(Chain { value: 'b', next: None }) }";
+    assert_eq!(format!("{:?}", chain), expected);
+
+    let expr = Expr::Block(Box::new(Block {
+        exprs: vec![Expr::Lit(1)],
+    }));
+    assert_eq!(format!("{:?}", expr), "Block(Block { exprs: [Lit(1)] })");
+
+    let tree = Tree {
+        value: 1,
+        children: vec![],
+    };
+    assert_eq!(format!("{:?}", tree), "Tree { value: 1, children: [] }");
+
+    let node = Node {
+        inner: other::Node(1),
+        linked: None,
+    };
+    assert_eq!(format!("{:?}", node), "Node { inner: Node(1), linked: None }");
+
+    let projected = Projected::<u8> {
+        value: 1,
+        marker: PhantomData,
+    };
+    assert_eq!(
+        format!("{:?}", projected),
+        "Projected { value: 1, marker: PhantomData<u8> }",
+    );
+}
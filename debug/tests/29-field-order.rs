@@ -0,0 +1,61 @@
+// Long structs read in logs and test failure diffs are easier to scan when
+// the fields that identify a value come first.
+//
+// #[debug(order = N)] on a field moves it ahead of the fields without one,
+// in increasing order of N. The remaining fields keep their declaration
+// order, or with #[debug(sort_fields)] on the struct or variant are sorted
+// by the name they are shown with. debug_fields, when #[debug(fields)]
+// generates it, reports fields in the same order.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(fields)]
+pub struct Job {
+    retries: u32,
+    #[debug(order = 1)]
+    status: &'static str,
+    owner: &'static str,
+    #[debug(order = 0)]
+    id: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(sort_fields)]
+pub struct Config {
+    verbose: bool,
+    #[debug(rename = "a_name")]
+    name: &'static str,
+    #[debug(order = 0)]
+    version: u32,
+    debug: bool,
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(u8, #[debug(order = 0)] &'static str);
+
+fn main() {
+    let job = Job {
+        retries: 2,
+        status: "running",
+        owner: "ci",
+        id: 42,
+    };
+    let expected = r#"Job { id: 42, status: "running", retries: 2, owner: "ci" }"#;
+    assert_eq!(format!("{:?}", job), expected);
+
+    let mut names = Vec::new();
+    job.debug_fields(&mut |name, _| names.push(name));
+    assert_eq!(names, ["id", "status", "retries", "owner"]);
+
+    let config = Config {
+        verbose: false,
+        name: "app",
+        version: 3,
+        debug: true,
+    };
+    let expected = r#"Config { version: 3, a_name: "app", debug: true, verbose: false }"#;
+    assert_eq!(format!("{:?}", config), expected);
+
+    assert_eq!(format!("{:?}", Pair(1, "one")), r#"Pair("one", 1)"#);
+}
@@ -0,0 +1,14 @@
+// Keys that only mean something on a field, such as skip, redact or order,
+// must not be silently ignored when written on the struct itself. Report them
+// as errors pointing at the key, the same way as unknown keys.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+#[debug(hex, order = 3)]
+pub struct Field {
+    name: &'static str,
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Likewise, keys that configure a whole struct, enum or union are rejected on
+// a field rather than having no effect.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field {
+    name: &'static str,
+    #[debug(hex, sort_fields)]
+    bitmask: u8,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// Truncation only knows how to walk the standard collections and strings. On
+// a field of any other type, such as an Option wrapping a collection, max_len
+// is an error pointing at the attribute rather than a guess at how to print
+// the value.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Packet {
+    #[debug(max_len = 2)]
+    payload: Option<Vec<u8>>,
+}
+
+fn main() {}
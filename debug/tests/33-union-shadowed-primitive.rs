@@ -0,0 +1,20 @@
+// Reading a union field is only sound when every bit pattern is valid for its
+// type. The derive recognizes integers and floats by name, so a name that has
+// been redefined to mean something else, here a bool, which only has two
+// valid bit patterns, must fail to compile rather than let Debug read an
+// invalid value.
+
+use derive_debug::CustomDebug;
+
+#[allow(non_camel_case_types)]
+type f32 = bool;
+
+#[derive(CustomDebug)]
+#[repr(C)]
+#[debug(union_tag = "flag")]
+pub union Flag {
+    flag: f32,
+    n: u32,
+}
+
+fn main() {}
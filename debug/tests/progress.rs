@@ -9,4 +9,29 @@ fn tests() {
     t.pass("tests/06-bound-trouble.rs");
     t.pass("tests/07-associated-type.rs");
     t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-enum.rs");
+    t.pass("tests/10-tuple-and-unit-struct.rs");
+    t.pass("tests/11-skip-and-redact.rs");
+    t.pass("tests/12-with-function.rs");
+    t.compile_fail("tests/13-unknown-attribute.rs");
+    t.compile_fail("tests/14-malformed-bound.rs");
+    t.compile_fail("tests/15-unresolved-bound.rs");
+    t.pass("tests/16-field-references.rs");
+    t.compile_fail("tests/17-unknown-field-reference.rs");
+    t.pass("tests/18-display.rs");
+    t.pass("tests/19-radix-shorthands.rs");
+    t.pass("tests/20-rename.rs");
+    t.pass("tests/21-max-len.rs");
+    t.pass("tests/22-bound-inference.rs");
+    t.pass("tests/23-phantom-markers.rs");
+    t.pass("tests/24-pretty-and-flags.rs");
+    t.pass("tests/25-union.rs");
+    t.pass("tests/26-redefined-prelude.rs");
+    t.pass("tests/27-debug-fields.rs");
+    t.pass("tests/28-recursive-types.rs");
+    t.pass("tests/29-field-order.rs");
+    t.compile_fail("tests/30-misplaced-container-attribute.rs");
+    t.compile_fail("tests/31-misplaced-field-attribute.rs");
+    t.compile_fail("tests/32-max-len-unsupported-type.rs");
+    t.compile_fail("tests/33-union-shadowed-primitive.rs");
 }